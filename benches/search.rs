@@ -0,0 +1,84 @@
+//! Compares searching with `Gameboard` clones against `Position` make/unmake.
+
+#[macro_use]
+extern crate criterion;
+extern crate royal_game_of_ur;
+
+use std::convert::TryFrom;
+
+use criterion::{black_box, Criterion};
+
+use royal_game_of_ur::bitboard::Position;
+use royal_game_of_ur::Gameboard;
+
+/// Counts the leaves of a fixed-roll search tree, cloning the board per move.
+fn perft_gameboard(gameboard: &Gameboard, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    (1..5)
+        .map(|roll| {
+            let mut rolled = gameboard.clone();
+            rolled.dice_roll = Some(roll);
+            let moves = rolled.legal_moves();
+            if moves.is_empty() {
+                rolled.pass_turn();
+                return perft_gameboard(&rolled, depth - 1);
+            }
+            moves
+                .into_iter()
+                .map(|cell| {
+                    let mut child = rolled.clone();
+                    child._move(cell);
+                    perft_gameboard(&child, depth - 1)
+                })
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+/// The same search with make/unmake on a single `Position`.
+fn perft_position(position: &mut Position, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut leaves = 0;
+    for roll in 1..5 {
+        let moves = position.moves(roll);
+        if moves == 0 {
+            position.pass();
+            leaves += perft_position(position, depth - 1);
+            position.pass();
+            continue;
+        }
+        for from in 0..15 {
+            if moves & 1 << from != 0 {
+                let undo = position.make_move(from, roll);
+                leaves += perft_position(position, depth - 1);
+                position.unmake_move(undo);
+            }
+        }
+    }
+    leaves
+}
+
+fn search(c: &mut Criterion) {
+    let gameboard = Gameboard::from_notation("3101000100000010/2000110011000001 1 -").unwrap();
+    let mut position = Position::try_from(&gameboard).unwrap();
+    assert_eq!(
+        perft_gameboard(&gameboard, 3),
+        perft_position(&mut position, 3)
+    );
+
+    c.bench_function("perft 3 gameboard", |b| {
+        b.iter(|| perft_gameboard(black_box(&gameboard), 3))
+    });
+    c.bench_function("perft 3 position", |b| {
+        b.iter(|| perft_position(black_box(&mut position), 3))
+    });
+    c.bench_function("new gameboard", |b| b.iter(Gameboard::new));
+    c.bench_function("new position", |b| b.iter(Position::new));
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
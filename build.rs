@@ -0,0 +1,30 @@
+#[cfg(feature = "ffi")]
+extern crate cbindgen;
+
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        use std::env;
+        use std::path::Path;
+
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-env-changed=UR_UPDATE_HEADER");
+        let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let mut config = cbindgen::Config::default();
+        config.language = cbindgen::Language::C;
+        config.include_guard = Some("ROYAL_GAME_OF_UR_H".to_string());
+        config.usize_is_size_t = true;
+        // Only `src/ffi.rs` makes up the ABI, not every public item.
+        let bindings = cbindgen::Builder::new()
+            .with_src(Path::new(&crate_dir).join("src/ffi.rs"))
+            .with_config(config)
+            .generate()
+            .expect("Could not generate C header");
+        let out_dir = env::var("OUT_DIR").unwrap();
+        bindings.write_to_file(Path::new(&out_dir).join("royal_game_of_ur.h"));
+        // The header in `include/` is checked in and only rewritten on request.
+        if env::var_os("UR_UPDATE_HEADER").is_some() {
+            bindings.write_to_file(Path::new(&crate_dir).join("include/royal_game_of_ur.h"));
+        }
+    }
+}
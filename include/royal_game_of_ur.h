@@ -0,0 +1,94 @@
+#ifndef ROYAL_GAME_OF_UR_H
+#define ROYAL_GAME_OF_UR_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Opaque game handle.
+ */
+typedef struct UrGame UrGame;
+
+/**
+ * Creates a game in the starting position.
+ */
+struct UrGame *ur_new(void);
+
+/**
+ * Destroys a game. Null is ignored.
+ *
+ * # Safety
+ * `game` must be null or a live handle not freed before.
+ */
+void ur_free(struct UrGame *game);
+
+/**
+ * Returns an independent copy of `game`.
+ *
+ * # Safety
+ * `game` must be a live handle.
+ */
+struct UrGame *ur_copy(const struct UrGame *game);
+
+/**
+ * Rolls the dice using `seed`. Returns the total, 0 to 4.
+ *
+ * # Safety
+ * `game` must be a live handle.
+ */
+int8_t ur_roll(struct UrGame *game, uint64_t seed);
+
+/**
+ * Writes up to `len` movable path cells into `moves` and returns how many
+ * legal moves there are in total.
+ *
+ * # Safety
+ * `game` must be a live handle and `moves` must point to `len` writable bytes.
+ */
+size_t ur_legal_moves(const struct UrGame *game, uint8_t *moves, size_t len);
+
+/**
+ * Moves the piece on path cell `cell`. Returns false if the move is illegal.
+ *
+ * # Safety
+ * `game` must be a live handle.
+ */
+bool ur_apply_move(struct UrGame *game, uint8_t cell);
+
+/**
+ * Passes the turn to the other player.
+ *
+ * # Safety
+ * `game` must be a live handle.
+ */
+void ur_pass_turn(struct UrGame *game);
+
+/**
+ * Returns 1 or 2 for the winning player, or 0 while the game is running.
+ *
+ * # Safety
+ * `game` must be a live handle.
+ */
+int32_t ur_winner(const struct UrGame *game);
+
+/**
+ * Writes the position's notation, NUL-terminated, into `buffer` if it fits
+ * in `len` bytes. Returns the notation length without the terminator.
+ *
+ * # Safety
+ * `game` must be a live handle and `buffer` must point to `len` writable bytes.
+ */
+size_t ur_to_notation(const struct UrGame *game, char *buffer, size_t len);
+
+/**
+ * Parses a position written by `ur_to_notation`. Returns null if invalid.
+ *
+ * # Safety
+ * `notation` must be a NUL-terminated string.
+ */
+struct UrGame *ur_from_notation(const char *notation);
+
+#endif /* ROYAL_GAME_OF_UR_H */
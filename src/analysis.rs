@@ -0,0 +1,160 @@
+//! Position evaluation.
+//!
+//! Win probabilities come from an expectiminimax search over the ruleset's
+//! exact dice distribution. Leaves are looked up in an endgame tablebase when
+//! one is given and covers them, and guessed with a race heuristic otherwise.
+
+use crate::gameboard::{Gameboard, Player};
+use crate::tablebase::Tablebase;
+
+/// Search depth in rolls used by the desktop analysis panel.
+pub const DEFAULT_DEPTH: u32 = 2;
+
+/// Expected outcome of one legal move.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveEvaluation {
+    /// Path cell of the piece to move.
+    pub cell: usize,
+    /// Probability that the mover wins after playing it.
+    pub win_probability: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    /// Probability that the first player wins.
+    pub first_player: f64,
+    /// Legal moves for the current roll, best first. Empty before rolling.
+    pub moves: Vec<MoveEvaluation>,
+}
+
+impl Analysis {
+    /// Probability that `player` wins.
+    pub fn win_probability(&self, player: Player) -> f64 {
+        match player {
+            Player::First => self.first_player,
+            Player::Second => 1.0 - self.first_player,
+        }
+    }
+}
+
+/// Evaluates `gameboard`, searching `depth` rolls ahead.
+pub fn analyse(gameboard: &Gameboard, depth: u32) -> Analysis {
+    analyse_with(gameboard, depth, None)
+}
+
+/// `analyse`, consulting `tablebase` where it covers the position.
+pub fn analyse_with(gameboard: &Gameboard, depth: u32, tablebase: Option<&Tablebase>) -> Analysis {
+    let mut moves = Vec::new();
+    let active = match gameboard.dice_roll {
+        None => chance(gameboard, depth, tablebase),
+        Some(_) => {
+            moves = gameboard
+                .legal_moves()
+                .into_iter()
+                .map(|cell| MoveEvaluation {
+                    cell,
+                    win_probability: after_move(gameboard, cell, depth.max(1), tablebase),
+                })
+                .collect();
+            moves.sort_by(|a, b| b.win_probability.total_cmp(&a.win_probability));
+            match moves.first() {
+                Some(best) => best.win_probability,
+                None => rolled(gameboard, depth.max(1), tablebase),
+            }
+        }
+    };
+    let first_player = match gameboard.active_player {
+        Player::First => active,
+        Player::Second => 1.0 - active,
+    };
+    Analysis {
+        first_player,
+        moves,
+    }
+}
+
+/// Probability that the side to move in `gameboard` wins, whether or not
+/// they have rolled.
+pub fn evaluate(gameboard: &Gameboard, depth: u32) -> f64 {
+    match gameboard.dice_roll {
+        Some(_) if !gameboard.is_finished() => rolled(gameboard, depth.max(1), None),
+        _ => chance(gameboard, depth, None),
+    }
+}
+
+/// Probability that the side to move wins, before they roll.
+fn chance(gameboard: &Gameboard, depth: u32, tablebase: Option<&Tablebase>) -> f64 {
+    if let Some(winner) = gameboard.winner() {
+        return if winner == gameboard.active_player {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    if let Some(value) = tablebase.and_then(|t| t.probe_gameboard(gameboard)) {
+        return value;
+    }
+    if depth == 0 {
+        return heuristic(gameboard);
+    }
+    gameboard
+        .ruleset
+        .dice
+        .outcomes()
+        .into_iter()
+        .map(|(roll, p)| {
+            let mut rolled_board = gameboard.clone();
+            rolled_board.dice_roll = Some(roll);
+            p * rolled(&rolled_board, depth, tablebase)
+        })
+        .sum()
+}
+
+/// Probability that the side to move wins with the roll already made.
+fn rolled(gameboard: &Gameboard, depth: u32, tablebase: Option<&Tablebase>) -> f64 {
+    let moves = gameboard.legal_moves();
+    if moves.is_empty() {
+        let mut passed = gameboard.clone();
+        passed.pass_turn();
+        return 1.0 - chance(&passed, depth - 1, tablebase);
+    }
+    moves
+        .into_iter()
+        .map(|cell| after_move(gameboard, cell, depth, tablebase))
+        .fold(0.0, f64::max)
+}
+
+/// Probability that the mover wins after moving the piece on `cell`.
+fn after_move(
+    gameboard: &Gameboard,
+    cell: usize,
+    depth: u32,
+    tablebase: Option<&Tablebase>,
+) -> f64 {
+    let mover = gameboard.active_player;
+    let mut child = gameboard.clone();
+    child._move(cell);
+    let value = chance(&child, depth - 1, tablebase);
+    if child.active_player == mover {
+        value
+    } else {
+        1.0 - value
+    }
+}
+
+/// Logistic guess from each side's total progress along the track, in pieces.
+fn heuristic(gameboard: &Gameboard) -> f64 {
+    let finish = gameboard.geometry.finish() as f64;
+    let progress = |cells: &[i8]| -> f64 {
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| f64::from(n) * i as f64 / finish)
+            .sum()
+    };
+    let (mine, theirs) = match gameboard.active_player {
+        Player::First => (&gameboard.player_1, &gameboard.player_2),
+        Player::Second => (&gameboard.player_2, &gameboard.player_1),
+    };
+    1.0 / (1.0 + (progress(theirs) - progress(mine)).exp())
+}
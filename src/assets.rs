@@ -0,0 +1,39 @@
+//! Finds the game's data files wherever the game is started from.
+//!
+//! Assets are looked up in these directories, in order:
+//!
+//! 1. `assets` next to the executable.
+//! 2. The directory named by the `UR_ASSETS` environment variable.
+//! 3. `assets` in the config directory, next to the settings.
+//! 4. `assets` in the working directory, for `cargo run`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::settings;
+
+/// Environment variable naming an assets directory.
+pub const ASSETS_ENV: &str = "UR_ASSETS";
+
+/// Directories searched for assets, in order.
+pub fn search_dirs() -> Vec<PathBuf> {
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("assets")));
+    let env_dir = env::var_os(ASSETS_ENV).map(PathBuf::from);
+    let config_dir = settings::config_dir().map(|dir| dir.join("assets"));
+    exe_dir
+        .into_iter()
+        .chain(env_dir)
+        .chain(config_dir)
+        .chain(Some(PathBuf::from("assets")))
+        .collect()
+}
+
+/// The first file or directory called `name` in the `search_dirs`.
+pub fn find<P: AsRef<Path>>(name: P) -> Option<PathBuf> {
+    search_dirs()
+        .into_iter()
+        .map(|dir| dir.join(name.as_ref()))
+        .find(|path| path.exists())
+}
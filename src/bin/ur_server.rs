@@ -0,0 +1,18 @@
+//! Serves the rules engine over HTTP and WebSocket.
+//!
+//! Usage: `ur_server [address]`, listening on `127.0.0.1:8080` by default.
+
+extern crate royal_game_of_ur;
+
+use std::env;
+
+use royal_game_of_ur::server::Server;
+
+fn main() {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let server = Server::bind(&addr[..]).expect("Could not bind server");
+    println!("Listening on http://{}", server.local_addr());
+    server.run();
+}
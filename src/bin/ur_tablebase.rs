@@ -0,0 +1,77 @@
+//! Generates and verifies endgame tablebases.
+//!
+//! Usage:
+//! - `ur_tablebase generate <max-remaining> <file> [pieces]` solves every
+//!   classic-rules position with at most `max-remaining` pieces left per side
+//!   (of 7 pieces by default) and writes it to `file`.
+//! - `ur_tablebase verify <file>` reloads a tablebase and checks every value
+//!   against its successors.
+
+extern crate royal_game_of_ur;
+
+use std::env;
+use std::process;
+use std::time::Instant;
+
+use royal_game_of_ur::rules;
+use royal_game_of_ur::tablebase::{self, Tablebase};
+
+/// Largest verification error a sound file can show after rounding values to
+/// 16 bits.
+const VERIFY_TOLERANCE: f64 = 1e-4;
+
+fn usage() -> ! {
+    eprintln!("Usage: ur_tablebase generate <max-remaining> <file> [pieces]");
+    eprintln!("       ur_tablebase verify <file>");
+    process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(|s| &s[..]) {
+        Some("generate") if args.len() == 3 || args.len() == 4 => {
+            let max_remaining = args[1].parse().unwrap_or_else(|_| usage());
+            let pieces = args
+                .get(3)
+                .map_or(7, |p| p.parse().unwrap_or_else(|_| usage()));
+            if max_remaining == 0 || max_remaining > pieces || pieces > rules::MAX_PIECES {
+                usage();
+            }
+            generate(max_remaining, pieces, &args[2]);
+        }
+        Some("verify") if args.len() == 2 => verify(&args[1]),
+        _ => usage(),
+    }
+}
+
+fn generate(max_remaining: u8, pieces: u8, path: &str) {
+    let start = Instant::now();
+    println!(
+        "Solving {} positions with at most {} of {} pieces left",
+        tablebase::positions(pieces, max_remaining).len(),
+        max_remaining,
+        pieces
+    );
+    let table = Tablebase::generate(pieces, max_remaining, |sweep, change| {
+        println!("Sweep {}: largest change {:.3e}", sweep, change);
+    });
+    table.write(path).expect("Could not write tablebase");
+    println!("Wrote {} in {:.1?}", path, start.elapsed());
+}
+
+fn verify(path: &str) {
+    let table = Tablebase::read(path).expect("Could not read tablebase");
+    println!(
+        "Checking {} positions with at most {} of {} pieces left",
+        table.len(),
+        table.max_remaining(),
+        table.pieces()
+    );
+    let error = table.verify();
+    println!("Largest error: {:.3e}", error);
+    if error > VERIFY_TOLERANCE {
+        println!("Verification failed");
+        process::exit(1);
+    }
+    println!("OK");
+}
@@ -0,0 +1,324 @@
+//! Compact position for search.
+//!
+//! `Position` holds the same rules as `Gameboard` in a few bytes: one bit per
+//! path cell for each player plus reserve and finished counts. It is `Copy`,
+//! never allocates and supports make/unmake, so bots and solvers can search
+//! without cloning a `Gameboard`. It only models the classic ruleset, with
+//! any number of pieces.
+
+use std::convert::TryFrom;
+
+use crate::gameboard::{Gameboard, Player};
+use crate::geometry::BoardGeometry;
+use crate::rules::{Ruleset, Trigger};
+
+/// Path cells 1 to 14, the ones a piece can stand on.
+const BOARD_MASK: u16 = 0x7FFE;
+/// Path cells 5 to 12, shared by both players.
+pub(crate) const SHARED_MASK: u16 = 0x1FE0;
+const PIECES: u8 = 7;
+
+/// Number of distinct values `Position::index` can return with seven pieces.
+pub const INDEX_COUNT: u64 = index_count(PIECES);
+
+/// Number of distinct values `Position::index` can return with `pieces` pieces.
+pub const fn index_count(pieces: u8) -> u64 {
+    let reserves = pieces as u64 + 1;
+    2 * 6561 * 64 * 64 * reserves * reserves
+}
+
+/// Whether boards under `ruleset` move like the classic rules, which are
+/// all a `Position` models: the standard track, no rosettes or safe squares
+/// and no extra turns. The dice and piece count may differ.
+pub fn supports(ruleset: &Ruleset) -> bool {
+    ruleset.rosettes.is_empty()
+        && ruleset.safe_squares.is_empty()
+        && ruleset.track.geometry() == BoardGeometry::STANDARD
+        && ruleset.extra_turns.captures == Trigger::Never
+        && ruleset.extra_turns.bearing_off == Trigger::Never
+}
+
+/// Board position without the dice roll.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Bit `i` is set when the player has a piece on path cell `i`.
+    pub(crate) lanes: [u16; 2],
+    pub(crate) reserve: [u8; 2],
+    pub(crate) finished: [u8; 2],
+    /// 0 when the first player is to move, 1 for the second.
+    pub(crate) side: u8,
+    /// Pieces per player.
+    pub(crate) pieces: u8,
+}
+
+/// What `make_move` changed, for `unmake_move`.
+#[derive(Clone, Copy, Debug)]
+pub struct Undo {
+    from: u8,
+    roll: u8,
+    captured: bool,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Position {
+    /// Starting position with seven pieces.
+    pub fn new() -> Position {
+        Position::with_pieces(PIECES)
+    }
+
+    /// Starting position with `pieces` pieces per player.
+    pub fn with_pieces(pieces: u8) -> Position {
+        Position {
+            lanes: [0; 2],
+            reserve: [pieces; 2],
+            finished: [0; 2],
+            side: 0,
+            pieces,
+        }
+    }
+
+    pub fn pieces(&self) -> u8 {
+        self.pieces
+    }
+
+    pub fn side_to_move(&self) -> Player {
+        if self.side == 0 {
+            Player::First
+        } else {
+            Player::Second
+        }
+    }
+
+    /// `player`'s pieces not yet borne off.
+    pub fn remaining(&self, player: Player) -> u8 {
+        let me = match player {
+            Player::First => 0,
+            Player::Second => 1,
+        };
+        self.pieces - self.finished[me]
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        if self.finished[0] == self.pieces {
+            Some(Player::First)
+        } else if self.finished[1] == self.pieces {
+            Some(Player::Second)
+        } else {
+            None
+        }
+    }
+
+    /// Bit `i` is set when the piece on path cell `i` can move by `roll`.
+    pub fn moves(&self, roll: u8) -> u16 {
+        if roll == 0 || self.winner().is_some() {
+            return 0;
+        }
+        let me = self.side as usize;
+        let lane = u32::from(self.lanes[me]);
+        let pieces = lane | u32::from(self.reserve[me] > 0);
+        // Landing on an own piece is illegal except when bearing off on cell 15.
+        let targets = (pieces << roll) & !lane & 0xFFFF;
+        (targets >> roll) as u16
+    }
+
+    /// Moves the side to move's piece on path cell `from` and passes the turn.
+    /// The move must be in `moves(roll)`.
+    pub fn make_move(&mut self, from: u8, roll: u8) -> Undo {
+        debug_assert!(self.moves(roll) & (1 << from) != 0);
+        let me = self.side as usize;
+        let them = 1 - me;
+        let to = from + roll;
+
+        if from == 0 {
+            self.reserve[me] -= 1;
+        } else {
+            self.lanes[me] &= !(1 << from);
+        }
+        let mut captured = false;
+        if to == 15 {
+            self.finished[me] += 1;
+        } else {
+            self.lanes[me] |= 1 << to;
+            if SHARED_MASK & self.lanes[them] & (1 << to) != 0 {
+                self.lanes[them] &= !(1 << to);
+                self.reserve[them] += 1;
+                captured = true;
+            }
+        }
+        self.side = them as u8;
+
+        Undo {
+            from,
+            roll,
+            captured,
+        }
+    }
+
+    /// Reverts the `make_move` that returned `undo`.
+    pub fn unmake_move(&mut self, undo: Undo) {
+        let them = self.side as usize;
+        let me = 1 - them;
+        let to = undo.from + undo.roll;
+
+        if undo.captured {
+            self.reserve[them] -= 1;
+            self.lanes[them] |= 1 << to;
+        }
+        if to == 15 {
+            self.finished[me] -= 1;
+        } else {
+            self.lanes[me] &= !(1 << to);
+        }
+        if undo.from == 0 {
+            self.reserve[me] += 1;
+        } else {
+            self.lanes[me] |= 1 << undo.from;
+        }
+        self.side = me as u8;
+    }
+
+    /// Passes the turn without moving.
+    pub fn pass(&mut self) {
+        self.side ^= 1;
+    }
+
+    /// Collision-free index below `index_count(self.pieces())`.
+    ///
+    /// The shared lane is read as a base-3 number, the private cells as bits,
+    /// then the reserves and side to move; finished counts follow from the rest.
+    pub fn index(&self) -> u64 {
+        let mut shared = 0u64;
+        for cell in (5..13).rev() {
+            shared = shared * 3 + self.cell_owner(cell);
+        }
+        let private = |lane: u16| u64::from((lane & 0x1E) >> 1 | (lane & 0x6000) >> 9);
+        let reserves = u64::from(self.pieces) + 1;
+
+        let mut index = u64::from(self.side);
+        index = index * 6561 + shared;
+        index = index * 64 + private(self.lanes[0]);
+        index = index * 64 + private(self.lanes[1]);
+        index = index * reserves + u64::from(self.reserve[0]);
+        index * reserves + u64::from(self.reserve[1])
+    }
+
+    /// Inverse of `index` for seven pieces. Returns `None` for indices of
+    /// impossible positions.
+    pub fn from_index(index: u64) -> Option<Position> {
+        Position::from_index_with_pieces(PIECES, index)
+    }
+
+    /// Inverse of `index` for `pieces` pieces.
+    pub fn from_index_with_pieces(pieces: u8, mut index: u64) -> Option<Position> {
+        if index >= index_count(pieces) {
+            return None;
+        }
+        let reserves = u64::from(pieces) + 1;
+        let mut take = |n: u64| {
+            let digit = index % n;
+            index /= n;
+            digit
+        };
+        let mut position = Position {
+            lanes: [0; 2],
+            reserve: [0; 2],
+            finished: [0; 2],
+            side: 0,
+            pieces,
+        };
+        position.reserve[1] = take(reserves) as u8;
+        position.reserve[0] = take(reserves) as u8;
+        for me in (0..2).rev() {
+            let private = take(64) as u16;
+            position.lanes[me] |= (private & 0xF) << 1 | (private & 0x30) << 9;
+        }
+        let mut shared = take(6561);
+        for cell in 5..13 {
+            match shared % 3 {
+                1 => position.lanes[0] |= 1 << cell,
+                2 => position.lanes[1] |= 1 << cell,
+                _ => {}
+            }
+            shared /= 3;
+        }
+        position.side = take(2) as u8;
+
+        for me in 0..2 {
+            let on_board = position.lanes[me].count_ones() as u8 + position.reserve[me];
+            if on_board > pieces {
+                return None;
+            }
+            position.finished[me] = pieces - on_board;
+        }
+        Some(position)
+    }
+
+    /// 0 for an empty shared cell, 1 or 2 for the player standing on it.
+    fn cell_owner(&self, cell: u8) -> u64 {
+        if self.lanes[0] & (1 << cell) != 0 {
+            1
+        } else if self.lanes[1] & (1 << cell) != 0 {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Piece counts per path cell, as in `Gameboard::player_1`.
+    fn cells(&self, me: usize) -> [i8; 16] {
+        let mut cells = [0; 16];
+        cells[0] = self.reserve[me] as i8;
+        cells[15] = self.finished[me] as i8;
+        for (i, cell) in cells.iter_mut().enumerate().take(15).skip(1) {
+            *cell = ((self.lanes[me] >> i) & 1) as i8;
+        }
+        cells
+    }
+
+    /// Converts back to a `Gameboard` with no roll.
+    pub fn to_gameboard(&self) -> Gameboard {
+        let mut g = Gameboard::with_ruleset(Ruleset {
+            pieces: self.pieces,
+            ..Ruleset::classic()
+        });
+        g.player_1 = self.cells(0).to_vec();
+        g.player_2 = self.cells(1).to_vec();
+        g.active_player = self.side_to_move();
+        g
+    }
+}
+
+/// Fails for boards whose rules `supports` rejects.
+impl<'a> TryFrom<&'a Gameboard> for Position {
+    type Error = String;
+
+    fn try_from(g: &'a Gameboard) -> Result<Position, String> {
+        if !supports(&g.ruleset) {
+            return Err(format!(
+                "the {} rules cannot be played on a Position",
+                g.ruleset.name
+            ));
+        }
+        let lane = |cells: &[i8]| {
+            (1..15)
+                .filter(|&i| cells[i] != 0)
+                .fold(0, |lane, i| lane | 1 << i)
+                & BOARD_MASK
+        };
+        Ok(Position {
+            lanes: [lane(&g.player_1), lane(&g.player_2)],
+            reserve: [g.player_1[0] as u8, g.player_2[0] as u8],
+            finished: [g.player_1[15] as u8, g.player_2[15] as u8],
+            side: match g.active_player {
+                Player::First => 0,
+                Player::Second => 1,
+            },
+            pieces: g.ruleset.pieces,
+        })
+    }
+}
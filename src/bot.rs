@@ -0,0 +1,130 @@
+//! Computer opponents.
+
+use rand::Rng;
+
+use crate::analysis;
+use crate::gameboard::{Gameboard, Player};
+use crate::logging::{GameEvent, GameLog};
+use crate::record::GameRecord;
+use crate::tablebase::Tablebase;
+
+/// A computer opponent the frontends can seat at either side.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Bot {
+    /// `choose_move`.
+    Greedy,
+    /// The best move found by the analysis search.
+    Search,
+}
+
+impl Bot {
+    pub const ALL: [Bot; 2] = [Bot::Greedy, Bot::Search];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Bot::Greedy => "greedy",
+            Bot::Search => "search",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Bot> {
+        Bot::ALL.iter().cloned().find(|bot| bot.name() == name)
+    }
+
+    /// Picks a move for the active player, or `None` if there is no legal move.
+    pub fn choose(self, gameboard: &Gameboard, tablebase: Option<&Tablebase>) -> Option<usize> {
+        match (self, tablebase) {
+            (Bot::Greedy, None) => choose_move(gameboard),
+            (Bot::Greedy, Some(tablebase)) => choose_move_with(gameboard, tablebase),
+            (Bot::Search, _) => {
+                analysis::analyse_with(gameboard, analysis::DEFAULT_DEPTH, tablebase)
+                    .moves
+                    .first()
+                    .map(|evaluation| evaluation.cell)
+            }
+        }
+    }
+}
+
+/// Plays `gameboard` to the end with `bots` seated first player then
+/// second, rolling with `rng`, and returns every turn played. Each event is
+/// logged, and written to `game_log` if given.
+pub fn play_out<R: Rng>(
+    mut gameboard: Gameboard,
+    bots: [Bot; 2],
+    tablebase: Option<&Tablebase>,
+    rng: &mut R,
+    mut game_log: Option<&mut GameLog>,
+) -> GameRecord {
+    let mut emit = |event: GameEvent| {
+        event.log();
+        if let Some(ref mut log) = game_log {
+            if let Err(e) = log.write(&event) {
+                warn!("Could not write the game log: {}", e);
+            }
+        }
+    };
+    emit(GameEvent::start(&gameboard));
+    let mut record = GameRecord::new(gameboard.ruleset.clone());
+    while !gameboard.is_finished() {
+        let player = gameboard.active_player;
+        if gameboard.dice_roll.is_none() {
+            let roll = gameboard.roll(rng);
+            emit(GameEvent::Roll { player, roll });
+        }
+        let bot = match player {
+            Player::First => bots[0],
+            Player::Second => bots[1],
+        };
+        match bot.choose(&gameboard, tablebase) {
+            Some(cell) => {
+                emit(GameEvent::play(&gameboard, cell));
+                record.play(&mut gameboard, cell);
+            }
+            None => {
+                emit(GameEvent::Pass { player });
+                record.pass(&mut gameboard);
+            }
+        }
+    }
+    if let Some(winner) = gameboard.winner() {
+        emit(GameEvent::End { winner });
+    }
+    record
+}
+
+/// Picks a move for the active player, or `None` if there is no legal move.
+///
+/// Prefers captures, then bearing off, then advancing the leading piece.
+pub fn choose_move(gameboard: &Gameboard) -> Option<usize> {
+    let roll = gameboard.dice_roll? as usize;
+    let finish = gameboard.geometry.finish();
+
+    gameboard
+        .legal_moves()
+        .into_iter()
+        .max_by_key(|&cell| (gameboard.captures(cell), cell + roll == finish, cell))
+}
+
+/// Plays perfectly when `tablebase` covers every position the roll can lead
+/// to, and falls back to `choose_move` otherwise.
+pub fn choose_move_with(gameboard: &Gameboard, tablebase: &Tablebase) -> Option<usize> {
+    let mover = gameboard.active_player;
+    let mut best = None;
+    for cell in gameboard.legal_moves() {
+        let mut child = gameboard.clone();
+        child._move(cell);
+        let value = if child.winner() == Some(mover) {
+            1.0
+        } else {
+            match tablebase.probe_gameboard(&child) {
+                Some(value) => 1.0 - value,
+                None => return choose_move(gameboard),
+            }
+        };
+        if best.is_none_or(|(_, best_value)| value > best_value) {
+            best = Some((cell, value));
+        }
+    }
+    best.map(|(cell, _)| cell)
+}
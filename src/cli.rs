@@ -0,0 +1,117 @@
+//! Command-line options of the game.
+
+use std::path::PathBuf;
+
+use log::LevelFilter;
+
+use crate::logging;
+use crate::settings::Settings;
+
+pub const USAGE: &str = "\
+Usage: royal_game_of_ur [options]
+
+Options:
+  --ruleset <name>     Rules preset, e.g. Classic or Masters
+  --seed <n>           Seed the dice
+  --p1 <player>        First player: human or bot:<name>
+  --p2 <player>        Second player: human or bot:<name>
+  --load <file>        Continue a saved game
+  --theme <name>       Colour theme
+  --window <W>x<H>     Window size in pixels
+  --headless           Play bot against bot without a window and print
+                       the game record
+  --font <file>        Font to draw text with
+  --log <level>        Log level: off, error, warn, info, debug or trace,
+                       instead of the UR_LOG environment variable
+  --game-log <dir>     Write a JSON log of each game to a file in dir
+  -h, --help           Print this help";
+
+/// Options given on the command line. Those left out keep the settings'
+/// values.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options {
+    pub ruleset: Option<String>,
+    pub seed: Option<u64>,
+    /// First player then second, as in `Settings`.
+    pub players: [Option<String>; 2],
+    pub load: Option<PathBuf>,
+    pub theme: Option<String>,
+    pub window: Option<[u32; 2]>,
+    pub headless: bool,
+    pub font: Option<PathBuf>,
+    pub log: Option<LevelFilter>,
+    /// Directory for a JSON log of each game.
+    pub game_log: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Options {
+    /// Parses the arguments after the program name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match &arg[..] {
+                "--ruleset" => options.ruleset = Some(value()?),
+                "--seed" => {
+                    let seed = value()?;
+                    options.seed = Some(
+                        seed.parse()
+                            .map_err(|_| format!("invalid seed \"{}\"", seed))?,
+                    );
+                }
+                "--p1" => options.players[0] = Some(value()?),
+                "--p2" => options.players[1] = Some(value()?),
+                "--load" => options.load = Some(PathBuf::from(value()?)),
+                "--theme" => options.theme = Some(value()?),
+                "--window" => options.window = Some(parse_window(&value()?)?),
+                "--headless" => options.headless = true,
+                "--font" => options.font = Some(PathBuf::from(value()?)),
+                "--log" => options.log = Some(logging::parse_level(&value()?)?),
+                "--game-log" => options.game_log = Some(PathBuf::from(value()?)),
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("unknown option \"{}\"", arg)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// `settings` with these options applied, checked like a settings file.
+    /// The theme is left for the caller to check against the themes loaded.
+    pub fn apply(&self, settings: &Settings) -> Result<Settings, String> {
+        let mut settings = settings.clone();
+        if let Some(ref ruleset) = self.ruleset {
+            settings.ruleset = ruleset.clone();
+        }
+        if let Some(ref player) = self.players[0] {
+            settings.first_player = player.clone();
+        }
+        if let Some(ref player) = self.players[1] {
+            settings.second_player = player.clone();
+        }
+        if let Some(ref theme) = self.theme {
+            settings.theme = theme.clone();
+        }
+        if let Some(window) = self.window {
+            settings.window = window;
+        }
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+/// Parses a window size such as `1280x768`.
+fn parse_window(size: &str) -> Result<[u32; 2], String> {
+    let invalid = || format!("invalid window size \"{}\"", size);
+    let mut parts = size.splitn(2, 'x');
+    let width = parts
+        .next()
+        .and_then(|w| w.parse().ok())
+        .ok_or_else(invalid)?;
+    let height = parts
+        .next()
+        .and_then(|h| h.parse().ok())
+        .ok_or_else(invalid)?;
+    Ok([width, height])
+}
@@ -0,0 +1,204 @@
+//! Chess-style clocks.
+//!
+//! A game clock holds each player's time for the whole game and gains an
+//! increment after each of their moves. A per-move limit caps the time for
+//! a single move, roll included. Either or both can be used.
+
+use rand::Rng;
+
+use crate::bot::Bot;
+use crate::gameboard::{Gameboard, Player};
+use crate::tablebase::Tablebase;
+
+/// What happens to a player whose time runs out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Timeout {
+    /// A random legal move is played for them.
+    #[default]
+    Random,
+    /// The search bot's move is played for them.
+    Best,
+    /// They lose the game.
+    Forfeit,
+}
+
+impl Timeout {
+    pub const ALL: [Timeout; 3] = [Timeout::Random, Timeout::Best, Timeout::Forfeit];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Timeout::Random => "Random move",
+            Timeout::Best => "Best move",
+            Timeout::Forfeit => "Forfeit",
+        }
+    }
+}
+
+/// Time allowed for a game, in seconds.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeControl {
+    /// Each player's time for the whole game, if limited.
+    pub game: Option<f64>,
+    /// Added to the game clock after each move.
+    #[serde(default)]
+    pub increment: f64,
+    /// Time for each move, if limited.
+    pub per_move: Option<f64>,
+    #[serde(default)]
+    pub timeout: Timeout,
+}
+
+impl TimeControl {
+    /// Time controls offered by the frontends, fastest last.
+    pub fn presets() -> Vec<TimeControl> {
+        let control = |game, increment, per_move| TimeControl {
+            game,
+            increment,
+            per_move,
+            timeout: Timeout::default(),
+        };
+        vec![
+            control(Some(600.), 5., None),
+            control(Some(300.), 3., None),
+            control(Some(180.), 0., Some(20.)),
+            control(None, 0., Some(15.)),
+            control(Some(60.), 1., None),
+        ]
+    }
+
+    /// Checks a time control read from a file or a request: limits must be
+    /// positive and the increment must not be negative.
+    pub fn validate(&self) -> Result<(), String> {
+        for &limit in self.game.iter().chain(&self.per_move) {
+            if !(limit.is_finite() && limit > 0.) {
+                return Err(format!("a time limit of {} s is not positive", limit));
+            }
+        }
+        if !(self.increment.is_finite() && self.increment >= 0.) {
+            return Err(format!("an increment of {} s is negative", self.increment));
+        }
+        Ok(())
+    }
+
+    /// Short description such as `5 min + 3 s`.
+    pub fn label(&self) -> String {
+        let game = self.game.map(|game| {
+            let minutes = format!("{} min", game / 60.);
+            if self.increment > 0. {
+                format!("{} + {} s", minutes, self.increment)
+            } else {
+                minutes
+            }
+        });
+        let per_move = self.per_move.map(|limit| format!("{} s per move", limit));
+        match (game, per_move) {
+            (Some(game), Some(per_move)) => format!("{}, {}", game, per_move),
+            (Some(label), None) | (None, Some(label)) => label,
+            (None, None) => "Untimed".to_string(),
+        }
+    }
+}
+
+/// Both players' clocks during a game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Clock {
+    pub control: TimeControl,
+    /// Game clock time left, first player then second.
+    pub remaining: [f64; 2],
+    /// Time spent on the move being played.
+    pub spent: f64,
+    /// Player who lost on time, if the game was forfeited.
+    pub forfeited: Option<Player>,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Clock {
+        let time = control.game.unwrap_or(0.);
+        Clock {
+            control,
+            remaining: [time, time],
+            spent: 0.,
+            forfeited: None,
+        }
+    }
+
+    /// Runs `player`'s clocks for `dt` seconds. Returns whether their time
+    /// is up.
+    pub fn tick(&mut self, player: Player, dt: f64) -> bool {
+        if self.forfeited.is_some() {
+            return false;
+        }
+        if self.control.game.is_some() {
+            let remaining = &mut self.remaining[index(player)];
+            *remaining = (*remaining - dt).max(0.);
+        }
+        self.spent += dt;
+        self.time_left(player) == Some(0.)
+    }
+
+    /// Time `player` has left for the move being played, if limited.
+    pub fn time_left(&self, player: Player) -> Option<f64> {
+        let game = self.control.game.map(|_| self.remaining[index(player)]);
+        let per_move = self
+            .control
+            .per_move
+            .map(|limit| (limit - self.spent).max(0.));
+        match (game, per_move) {
+            (Some(game), Some(per_move)) => Some(game.min(per_move)),
+            (game, per_move) => game.or(per_move),
+        }
+    }
+
+    /// Ends `player`'s move: adds the increment and restarts the move clock.
+    pub fn end_move(&mut self, player: Player) {
+        if self.control.game.is_some() {
+            self.remaining[index(player)] += self.control.increment;
+        }
+        self.spent = 0.;
+    }
+
+    /// The winner, if a player lost on time.
+    pub fn winner(&self) -> Option<Player> {
+        self.forfeited.map(Player::other)
+    }
+}
+
+fn index(player: Player) -> usize {
+    match player {
+        Player::First => 0,
+        Player::Second => 1,
+    }
+}
+
+/// Move played for the active player when their time runs out, as
+/// `timeout` says: a random legal move or the search bot's. `None` passes.
+/// The dice must have been rolled. Forfeits are left to the caller.
+pub fn timeout_move<R: Rng>(
+    gameboard: &Gameboard,
+    timeout: Timeout,
+    tablebase: Option<&Tablebase>,
+    rng: &mut R,
+) -> Option<usize> {
+    match timeout {
+        Timeout::Best => Bot::Search.choose(gameboard, tablebase),
+        Timeout::Random | Timeout::Forfeit => {
+            let moves = gameboard.legal_moves();
+            if moves.is_empty() {
+                None
+            } else {
+                Some(moves[rng.gen_range(0, moves.len())])
+            }
+        }
+    }
+}
+
+/// Formats seconds as `m:ss`, or with tenths under ten seconds.
+pub fn format_time(seconds: f64) -> String {
+    if seconds < 10. {
+        format!("{:.1}", seconds)
+    } else {
+        let seconds = seconds.ceil() as u64;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
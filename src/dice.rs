@@ -0,0 +1,142 @@
+//! Dice models.
+//!
+//! Every model has an exact distribution over roll totals, so searches can
+//! weight chance nodes without sampling.
+
+use rand::Rng;
+
+/// How a roll is thrown.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Dice {
+    /// Sum of `n` two-sided dice: coins, or tetrahedral dice with two of four
+    /// tips marked.
+    Binary(u8),
+    /// One die showing `low..=high` with equal probability.
+    Uniform(u8, u8),
+    /// Sum of `dice` two-sided dice where a total of `from` counts as `to`,
+    /// e.g. zero counting as five.
+    Remapped { dice: u8, from: u8, to: u8 },
+    /// Probability of each total, starting at 0. Build with `Dice::table` to
+    /// have it checked.
+    Table(Vec<f64>),
+}
+
+impl Default for Dice {
+    fn default() -> Self {
+        Dice::Binary(4)
+    }
+}
+
+impl Dice {
+    /// A probability table, or `None` unless the probabilities are
+    /// non-negative and sum to one.
+    pub fn table(probabilities: Vec<f64>) -> Option<Dice> {
+        let dice = Dice::Table(probabilities);
+        dice.validate().ok().map(|_| dice)
+    }
+
+    /// Checks dice read from a file, whose totals must fit an `i8`.
+    pub fn validate(&self) -> Result<(), String> {
+        let max = i8::MAX as u8;
+        match *self {
+            Dice::Binary(n) if n > max => Err(format!("{} dice is too many", n)),
+            Dice::Uniform(low, high) if low > high || high >= max => {
+                Err(format!("{} to {} is not a valid die", low, high))
+            }
+            Dice::Remapped { dice, from, to } if dice > max || from > dice || to > max => Err(
+                format!("{} dice remapping {} to {} is not valid", dice, from, to),
+            ),
+            Dice::Table(ref probabilities) => {
+                let total: f64 = probabilities.iter().sum();
+                if probabilities.len() > max as usize + 1
+                    || !probabilities.iter().all(|&p| p >= 0.0)
+                    || (total - 1.0).abs() >= 1e-9
+                {
+                    Err("the probability table is not a distribution".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Probability of each total from 0 to `max()`.
+    pub fn distribution(&self) -> Vec<f64> {
+        match *self {
+            Dice::Binary(n) => binary(n),
+            Dice::Uniform(low, high) => {
+                let p = 1.0 / f64::from(high - low + 1);
+                (0..=high).map(|i| if i < low { 0.0 } else { p }).collect()
+            }
+            Dice::Remapped { dice, from, to } => {
+                let mut distribution = binary(dice);
+                let moved = distribution.get(from as usize).cloned().unwrap_or(0.0);
+                if (to as usize) >= distribution.len() {
+                    distribution.resize(to as usize + 1, 0.0);
+                }
+                if let Some(p) = distribution.get_mut(from as usize) {
+                    *p = 0.0;
+                }
+                distribution[to as usize] += moved;
+                distribution
+            }
+            Dice::Table(ref probabilities) => probabilities.clone(),
+        }
+    }
+
+    /// Totals that can come up, with their probabilities.
+    pub fn outcomes(&self) -> Vec<(i8, f64)> {
+        self.distribution()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, p)| p > 0.0)
+            .map(|(roll, p)| (roll as i8, p))
+            .collect()
+    }
+
+    /// Highest total that can be represented.
+    pub fn max(&self) -> i8 {
+        self.distribution().len() as i8 - 1
+    }
+
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> i8 {
+        match *self {
+            Dice::Binary(n) => (0..n).map(|_| rng.gen_range(0, 2)).sum(),
+            Dice::Uniform(low, high) => rng.gen_range(low as i8, high as i8 + 1),
+            Dice::Remapped { dice, from, to } => {
+                let total = Dice::Binary(dice).roll(rng);
+                if total == from as i8 {
+                    to as i8
+                } else {
+                    total
+                }
+            }
+            Dice::Table(ref probabilities) => {
+                let mut x: f64 = rng.gen();
+                for (roll, &p) in probabilities.iter().enumerate() {
+                    if x < p {
+                        return roll as i8;
+                    }
+                    x -= p;
+                }
+                // Rounding left a sliver at the top; give it to the last outcome.
+                probabilities.iter().rposition(|&p| p > 0.0).unwrap_or(0) as i8
+            }
+        }
+    }
+}
+
+/// Binomial distribution of `n` fair two-sided dice.
+fn binary(n: u8) -> Vec<f64> {
+    let mut distribution = vec![1.0];
+    for _ in 0..n {
+        let mut next = vec![0.0; distribution.len() + 1];
+        for (total, &p) in distribution.iter().enumerate() {
+            next[total] += p / 2.0;
+            next[total + 1] += p / 2.0;
+        }
+        distribution = next;
+    }
+    distribution
+}
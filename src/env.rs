@@ -0,0 +1,131 @@
+//! Self-play environment for reinforcement learning.
+//!
+//! The environment rolls for the side to move and passes automatically while
+//! there is nothing to play, so every observation has at least one legal action.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::gameboard::{Gameboard, Player};
+use crate::rules::Ruleset;
+
+/// `Environment::actions` under the classic rules.
+pub const ACTIONS: usize = 15;
+
+/// `Environment::observation_size` under the classic rules.
+pub const OBSERVATION_SIZE: usize = 16 + 16 + 5 + 1;
+
+/// Result of a legal action.
+pub struct Step {
+    /// 1 if the action won the game for the player who took it, else 0.
+    pub reward: f32,
+    pub done: bool,
+}
+
+pub struct Environment {
+    gameboard: Gameboard,
+    rng: StdRng,
+}
+
+impl Environment {
+    /// An environment playing the classic rules.
+    pub fn new(seed: u64) -> Environment {
+        Environment::with_ruleset(Ruleset::classic(), seed)
+    }
+
+    pub fn with_ruleset(ruleset: Ruleset, seed: u64) -> Environment {
+        let mut env = Environment {
+            gameboard: Gameboard::with_ruleset(ruleset),
+            rng: StdRng::seed_from_u64(seed),
+        };
+        env.advance();
+        env
+    }
+
+    /// Number of actions: action `i` moves the piece on path cell `i`.
+    pub fn actions(&self) -> usize {
+        self.gameboard.geometry.path_len() - 1
+    }
+
+    /// Length of an observation: `player_1` and `player_2` divided by the
+    /// number of pieces, the roll one-hot and the side to move (0 first,
+    /// 1 second).
+    pub fn observation_size(&self) -> usize {
+        2 * self.gameboard.geometry.path_len() + self.rolls() + 1
+    }
+
+    /// Starts a new game, reseeding the dice if `seed` is given.
+    pub fn reset(&mut self, seed: Option<u64>) {
+        if let Some(seed) = seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+        self.gameboard = Gameboard::with_ruleset(self.gameboard.ruleset.clone());
+        self.advance();
+    }
+
+    /// Plays `action` for the side to move. Returns `None` if it is illegal.
+    pub fn step(&mut self, action: usize) -> Option<Step> {
+        if action >= self.actions() || !self.gameboard._move(action) {
+            return None;
+        }
+        let done = self.gameboard.is_finished();
+        self.advance();
+        Some(Step {
+            reward: if done { 1.0 } else { 0.0 },
+            done,
+        })
+    }
+
+    /// Writes the observation into `out`, which must hold
+    /// `observation_size()` values.
+    pub fn observe(&self, out: &mut [f32]) {
+        assert_eq!(out.len(), self.observation_size());
+        let g = &self.gameboard;
+        let path_len = g.geometry.path_len();
+        let pieces = f32::from(g.ruleset.pieces);
+        for i in 0..path_len {
+            out[i] = f32::from(g.player_1[i]) / pieces;
+            out[path_len + i] = f32::from(g.player_2[i]) / pieces;
+        }
+        let rolls = &mut out[2 * path_len..2 * path_len + self.rolls()];
+        for (i, cell) in rolls.iter_mut().enumerate() {
+            *cell = if g.dice_roll == Some(i as i8) {
+                1.0
+            } else {
+                0.0
+            };
+        }
+        out[2 * path_len + self.rolls()] = match g.active_player {
+            Player::First => 0.0,
+            Player::Second => 1.0,
+        };
+    }
+
+    pub fn legal_action_mask(&self) -> Vec<bool> {
+        let mut mask = vec![false; self.actions()];
+        for cell in self.gameboard.legal_moves() {
+            mask[cell] = true;
+        }
+        mask
+    }
+
+    pub fn gameboard(&self) -> &Gameboard {
+        &self.gameboard
+    }
+
+    /// Number of possible roll totals, from 0 to the dice's highest.
+    fn rolls(&self) -> usize {
+        self.gameboard.ruleset.dice.max() as usize + 1
+    }
+
+    /// Rolls, passing until the side to move has a legal move or the game is over.
+    fn advance(&mut self) {
+        while !self.gameboard.is_finished() {
+            self.gameboard.roll(&mut self.rng);
+            if !self.gameboard.legal_moves().is_empty() {
+                break;
+            }
+            self.gameboard.pass_turn();
+        }
+    }
+}
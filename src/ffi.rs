@@ -0,0 +1,137 @@
+//! C ABI over `Gameboard`, built into the `cdylib`.
+//!
+//! The header `include/royal_game_of_ur.h` is generated from this module;
+//! rebuild with `UR_UPDATE_HEADER=1` after changing it. Every function
+//! taking a `UrGame` pointer requires a live pointer from `ur_new`, `ur_copy`
+//! or `ur_from_notation`; pass it to `ur_free` exactly once when done.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::gameboard::{Gameboard, Player};
+
+/// Opaque game handle.
+pub struct UrGame {
+    gameboard: Gameboard,
+}
+
+fn into_raw(gameboard: Gameboard) -> *mut UrGame {
+    Box::into_raw(Box::new(UrGame { gameboard }))
+}
+
+/// Creates a game in the starting position.
+#[no_mangle]
+pub extern "C" fn ur_new() -> *mut UrGame {
+    into_raw(Gameboard::new())
+}
+
+/// Destroys a game. Null is ignored.
+///
+/// # Safety
+/// `game` must be null or a live handle not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn ur_free(game: *mut UrGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Returns an independent copy of `game`.
+///
+/// # Safety
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn ur_copy(game: *const UrGame) -> *mut UrGame {
+    into_raw((*game).gameboard.clone())
+}
+
+/// Rolls the dice using `seed`. Returns the total, 0 to 4.
+///
+/// # Safety
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn ur_roll(game: *mut UrGame, seed: u64) -> i8 {
+    (*game).gameboard.roll(&mut StdRng::seed_from_u64(seed))
+}
+
+/// Writes up to `len` movable path cells into `moves` and returns how many
+/// legal moves there are in total.
+///
+/// # Safety
+/// `game` must be a live handle and `moves` must point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ur_legal_moves(game: *const UrGame, moves: *mut u8, len: usize) -> usize {
+    let legal = (*game).gameboard.legal_moves();
+    for (i, &cell) in legal.iter().take(len).enumerate() {
+        *moves.add(i) = cell as u8;
+    }
+    legal.len()
+}
+
+/// Moves the piece on path cell `cell`. Returns false if the move is illegal.
+///
+/// # Safety
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn ur_apply_move(game: *mut UrGame, cell: u8) -> bool {
+    (cell as usize) < (*game).gameboard.geometry.path_len()
+        && (*game).gameboard._move(cell as usize)
+}
+
+/// Passes the turn to the other player.
+///
+/// # Safety
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn ur_pass_turn(game: *mut UrGame) {
+    (*game).gameboard.pass_turn();
+}
+
+/// Returns 1 or 2 for the winning player, or 0 while the game is running.
+///
+/// # Safety
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn ur_winner(game: *const UrGame) -> i32 {
+    match (*game).gameboard.winner() {
+        Some(Player::First) => 1,
+        Some(Player::Second) => 2,
+        None => 0,
+    }
+}
+
+/// Writes the position's notation, NUL-terminated, into `buffer` if it fits
+/// in `len` bytes. Returns the notation length without the terminator.
+///
+/// # Safety
+/// `game` must be a live handle and `buffer` must point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ur_to_notation(
+    game: *const UrGame,
+    buffer: *mut c_char,
+    len: usize,
+) -> usize {
+    let notation = (*game).gameboard.to_notation();
+    if notation.len() < len {
+        ptr::copy_nonoverlapping(notation.as_ptr(), buffer as *mut u8, notation.len());
+        *buffer.add(notation.len()) = 0;
+    }
+    notation.len()
+}
+
+/// Parses a position written by `ur_to_notation`. Returns null if invalid.
+///
+/// # Safety
+/// `notation` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ur_from_notation(notation: *const c_char) -> *mut UrGame {
+    CStr::from_ptr(notation)
+        .to_str()
+        .ok()
+        .and_then(Gameboard::from_notation)
+        .map_or(ptr::null_mut(), into_raw)
+}
@@ -0,0 +1,132 @@
+//! Screens made of setting rows above a line of buttons, like the menu.
+//!
+//! Clicking the left or right half of a row's value box steps it back or
+//! forward. Up and down pick a row and left and right step it.
+
+use piston::input::{GenericEvent, Key};
+
+/// Top of the first setting row.
+pub const ROWS_TOP: f64 = 64.;
+pub const ROW_HEIGHT: f64 = 30.;
+/// Left edge and width of the value boxes.
+pub const VALUE_LEFT: f64 = 300.;
+pub const VALUE_WIDTH: f64 = 300.;
+/// Top of the buttons, laid out from the left margin.
+pub const BUTTONS_TOP: f64 = 340.;
+pub const BUTTONS_LEFT: f64 = 40.;
+pub const BUTTON_WIDTH: f64 = 140.;
+pub const BUTTON_HEIGHT: f64 = 50.;
+pub const BUTTON_GAP: f64 = 20.;
+
+/// What happened on a form.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FormEvent {
+    /// Step `row` forward, or back if `false`.
+    Step(usize, bool),
+    Button(usize),
+    /// Any key the form does not use itself.
+    Key(Key),
+}
+
+pub struct Form {
+    /// Row that the arrow keys change.
+    pub selected: usize,
+    pub cursor_pos: [f64; 2],
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    pub fn new() -> Form {
+        Form {
+            selected: 0,
+            cursor_pos: [0.0; 2],
+        }
+    }
+
+    /// Handles `e` for a form of `rows` rows and `buttons` buttons.
+    pub fn event<E: GenericEvent>(
+        &mut self,
+        e: &E,
+        rows: usize,
+        buttons: usize,
+    ) -> Option<FormEvent> {
+        use piston::input::{Button, MouseButton};
+
+        if let Some(pos) = e.mouse_cursor_args() {
+            self.cursor_pos = pos;
+        }
+        let [x, y] = self.cursor_pos;
+
+        match e.press_args() {
+            Some(Button::Mouse(MouseButton::Left)) => {
+                let row = ((y - ROWS_TOP) / ROW_HEIGHT).floor();
+                if (VALUE_LEFT..=VALUE_LEFT + VALUE_WIDTH).contains(&x)
+                    && row >= 0.
+                    && (row as usize) < rows
+                {
+                    self.selected = row as usize;
+                    // Left half steps back, right half forward.
+                    return Some(FormEvent::Step(
+                        self.selected,
+                        x > VALUE_LEFT + VALUE_WIDTH / 2.,
+                    ));
+                }
+                if (BUTTONS_TOP..=BUTTONS_TOP + BUTTON_HEIGHT).contains(&y) {
+                    let offset = x - BUTTONS_LEFT;
+                    let button = (offset / (BUTTON_WIDTH + BUTTON_GAP)).floor();
+                    if button >= 0.
+                        && (button as usize) < buttons
+                        && offset % (BUTTON_WIDTH + BUTTON_GAP) <= BUTTON_WIDTH
+                    {
+                        return Some(FormEvent::Button(button as usize));
+                    }
+                }
+                None
+            }
+            Some(Button::Keyboard(key)) => match key {
+                Key::Up => {
+                    self.selected = step(rows, self.selected, false);
+                    None
+                }
+                Key::Down => {
+                    self.selected = step(rows, self.selected, true);
+                    None
+                }
+                Key::Left => Some(FormEvent::Step(self.selected, false)),
+                Key::Right => Some(FormEvent::Step(self.selected, true)),
+                _ => Some(FormEvent::Key(key)),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Index after `i` among `len` choices, or before it if `!forward`,
+/// wrapping around.
+pub fn step(len: usize, i: usize, forward: bool) -> usize {
+    if forward {
+        (i + 1) % len
+    } else {
+        (i + len - 1) % len
+    }
+}
+
+/// Index of the choice after `current` in `choices`, or before it if
+/// `!forward`. Starts from `fallback` if `current` is not a choice.
+pub fn step_choice<T: PartialEq>(
+    choices: &[T],
+    current: &T,
+    fallback: usize,
+    forward: bool,
+) -> usize {
+    let i = choices
+        .iter()
+        .position(|c| c == current)
+        .unwrap_or(fallback);
+    step(choices.len(), i, forward)
+}
@@ -1,40 +1,73 @@
-use std::collections::HashMap;
+use rand::Rng;
 
-use rand::{self, Rng};
+use crate::geometry::BoardGeometry;
+use crate::rules::Ruleset;
 
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Player {
     First,
     Second,
 }
 
+impl Player {
+    pub fn other(self) -> Player {
+        match self {
+            Player::First => Player::Second,
+            Player::Second => Player::First,
+        }
+    }
+}
+
+/// Snapshot of a game for clients outside the desktop UI.
+#[derive(Serialize)]
+pub struct GameState {
+    pub player_1: Vec<i8>,
+    pub player_2: Vec<i8>,
+    pub active_player: Player,
+    pub dice_roll: Option<i8>,
+    pub legal_moves: Vec<usize>,
+    pub winner: Option<Player>,
+}
+
 /// Stores board information
+#[derive(Clone)]
 pub struct Gameboard {
     pub cells: [[u8; 8]; 3],
-    pub player_1: [i8; 16],
-    pub player_2: [i8; 16],
+    pub player_1: Vec<i8>,
+    pub player_2: Vec<i8>,
     pub active_player: Player,
     pub dice_roll: Option<i8>,
-    pub grid_to_path_1: HashMap<(i8, i8), i8>,
-    pub grid_to_path_2: HashMap<(i8, i8), i8>,
-    pub path_to_grid_1: HashMap<i8, (i8, i8)>,
-    pub path_to_grid_2: HashMap<i8, (i8, i8)>,
+    /// Whether the active player is moving again after their last move.
+    pub extra_turn: bool,
+    pub geometry: BoardGeometry,
+    pub ruleset: Ruleset,
+}
+
+impl Default for Gameboard {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Gameboard {
     pub fn new() -> Gameboard {
-        let mut g = Gameboard {
+        Gameboard::with_ruleset(Ruleset::default())
+    }
+
+    pub fn with_ruleset(ruleset: Ruleset) -> Gameboard {
+        let geometry = ruleset.track.geometry();
+        let mut start = vec![0; geometry.path_len()];
+        start[0] = ruleset.pieces as i8;
+        Gameboard {
             cells: [[0; 8]; 3],
-            player_1: [7, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-            player_2: [7, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            player_1: start.clone(),
+            player_2: start,
             active_player: Player::First,
             dice_roll: None,
-            grid_to_path_1: HashMap::new(),
-            grid_to_path_2: HashMap::new(),
-            path_to_grid_2: HashMap::new(),
-            path_to_grid_1: HashMap::new(),
-        };
-        g.populate();
-        return g;
+            extra_turn: false,
+            geometry,
+            ruleset,
+        }
     }
     /*
     pub fn is_player_1(&self, ind: usize) -> bool {
@@ -45,113 +78,243 @@ impl Gameboard {
         return self.player_2[ind] == 1;
     }
     */
-    pub fn roll(&mut self) {
-        //roll d2 x 4
-        let mut rng = rand::thread_rng();
-        let mut result: i8 = 0;
-
-        for i in 0..4 {
-            let num: i8 = rng.gen_range(0, 2);
-            println!("{} die: {}", i, num);
-            result += num;
-        }
+    /// Rolls the ruleset's dice. Returns the total, also stored in `dice_roll`.
+    pub fn roll<R: Rng>(&mut self, rng: &mut R) -> i8 {
+        let result = self.ruleset.dice.roll(rng);
 
         self.dice_roll = Some(result);
         //Pass turn if roll is 0.
-
-        println!("Total: {}", self.dice_roll.unwrap());
+        result
     }
 
-    pub fn _move(&mut self, i: usize) {
-        let (p1, p2): (&mut [i8; 16], &mut [i8; 16]) = match self.active_player {
+    /// Moves the active player's piece from path cell `i` by the current roll.
+    /// Returns `false` and leaves the board untouched if the move is illegal.
+    pub fn _move(&mut self, i: usize) -> bool {
+        let tmp_roll = match self.dice_roll {
+            Some(roll) => roll as usize,
+            None => return false,
+        };
+        if !self.move_is_valid(i, tmp_roll) {
+            return false;
+        }
+
+        let target = i + tmp_roll;
+        let captured = self.opponent_at(target);
+        let bonus = self.ruleset.extra_turns;
+        let roll = tmp_roll as i8;
+        let extra_turn = bonus.rosettes.fires(roll) && self.is_rosette(target)
+            || bonus.captures.fires(roll) && captured.is_some()
+            || bonus.bearing_off.fires(roll) && target == self.geometry.finish();
+        let (p1, p2) = match self.active_player {
             Player::First => (&mut self.player_1, &mut self.player_2),
             Player::Second => (&mut self.player_2, &mut self.player_1),
         };
 
-        let tmp_roll = self.dice_roll.unwrap() as usize;
+        // Move
+        p1[i] -= 1;
+        p1[target] += 1;
+        // Knock out?
+        if let Some(k) = captured {
+            p2[k] = 0;
+            p2[0] += 1;
+        }
 
-        if Gameboard::move_is_valid(&*p1, i, tmp_roll) {
-            // Move
-            p1[i] -= 1;
-            p1[i + tmp_roll] += 1;
-            // Knock out?
-            if p2[i + tmp_roll] == 1 && (i + tmp_roll) > 4 && (i + tmp_roll) < 13 {
-                p2[i + tmp_roll] = 0;
-                p2[0] += 1;
-            }
+        self.end_turn(extra_turn);
+        true
+    }
 
-            self.pass_turn();
+    /// Path cells of the active player's pieces that can move with the current roll.
+    pub fn legal_moves(&self) -> Vec<usize> {
+        match self.dice_roll {
+            Some(roll) if !self.is_finished() => (0..self.geometry.finish())
+                .filter(|&i| self.move_is_valid(i, roll as usize))
+                .collect(),
+            _ => Vec::new(),
         }
     }
 
-    fn move_is_valid(array: &[i8; 16], cell_id: usize, dice_roll: usize) -> bool {
-        return (cell_id + dice_roll) < 16
-            && (array[cell_id + dice_roll] != 1 || cell_id + dice_roll == 15)
+    fn move_is_valid(&self, cell_id: usize, dice_roll: usize) -> bool {
+        let array = match self.active_player {
+            Player::First => &self.player_1,
+            Player::Second => &self.player_2,
+        };
+        let target = cell_id + dice_roll;
+        target < self.geometry.path_len()
+            && (array[target] != 1 || target == self.geometry.finish())
             && array[cell_id] >= 1
-            && dice_roll > 0;
+            && dice_roll > 0
+            && !(self.opponent_at(target).is_some() && self.is_safe(target))
+    }
+
+    /// Whether moving the piece on path cell `cell` by the current roll captures.
+    pub fn captures(&self, cell: usize) -> bool {
+        self.dice_roll
+            .is_some_and(|roll| self.opponent_at(cell + roll as usize).is_some())
+    }
+
+    /// Opponent's path cell if they have a piece on the active player's path cell `target`.
+    fn opponent_at(&self, target: usize) -> Option<usize> {
+        let player = self.active_player;
+        let opponent = match player {
+            Player::First => &self.player_2,
+            Player::Second => &self.player_1,
+        };
+        self.geometry
+            .grid_cell(player, target)
+            .and_then(|cell| self.geometry.path_cell(player.other(), cell))
+            .filter(|&k| k > 0 && k < self.geometry.finish() && opponent[k] == 1)
+    }
+
+    /// Whether the active player's path cell `path` is a rosette.
+    fn is_rosette(&self, path: usize) -> bool {
+        self.geometry
+            .grid_cell(self.active_player, path)
+            .is_some_and(|cell| self.ruleset.is_rosette(cell))
+    }
+
+    /// Whether the active player's path cell `path` is a safe square.
+    fn is_safe(&self, path: usize) -> bool {
+        self.geometry
+            .grid_cell(self.active_player, path)
+            .is_some_and(|cell| self.ruleset.is_safe(cell))
+    }
+
+    /// Ends a move: the same player rolls again if `extra_turn` and the game
+    /// is not over, otherwise the turn passes.
+    fn end_turn(&mut self, extra_turn: bool) {
+        if extra_turn && !self.is_finished() {
+            self.dice_roll = None;
+            self.extra_turn = true;
+        } else {
+            self.pass_turn();
+        }
     }
 
     pub fn pass_turn(&mut self) {
         self.dice_roll = None;
-        match self.active_player {
-            Player::First => self.active_player = Player::Second,
-            Player::Second => self.active_player = Player::First,
-        }
+        self.extra_turn = false;
+        self.active_player = self.active_player.other();
     }
 
     pub fn is_finished(&self) -> bool {
-        return (self.player_1[15] == 7) || (self.player_2[15] == 7);
+        self.winner().is_some()
     }
 
-    pub fn get_active_cell(&self, x: i8, y: i8) -> usize {
-        match self.active_player {
-            Player::First => self.grid_to_path_1[&(x, y)] as usize,
-            Player::Second => self.grid_to_path_2[&(x, y)] as usize,
+    pub fn winner(&self) -> Option<Player> {
+        let finish = self.geometry.finish();
+        let pieces = self.ruleset.pieces as i8;
+        if self.player_1[finish] == pieces {
+            Some(Player::First)
+        } else if self.player_2[finish] == pieces {
+            Some(Player::Second)
+        } else {
+            None
         }
     }
 
-    pub fn populate(&mut self) {
-        // Populate hash map for the first player
-        self.grid_to_path_1.insert((0, 0), 4);
-        self.grid_to_path_1.insert((0, 1), 3);
-        self.grid_to_path_1.insert((0, 2), 2);
-        self.grid_to_path_1.insert((0, 3), 1);
-        self.grid_to_path_1.insert((0, 4), 0);
-        self.grid_to_path_1.insert((0, 5), 15);
-        self.grid_to_path_1.insert((0, 6), 14);
-        self.grid_to_path_1.insert((0, 7), 13);
-        self.grid_to_path_1.insert((1, 0), 5);
-        self.grid_to_path_1.insert((1, 1), 6);
-        self.grid_to_path_1.insert((1, 2), 7);
-        self.grid_to_path_1.insert((1, 3), 8);
-        self.grid_to_path_1.insert((1, 4), 9);
-        self.grid_to_path_1.insert((1, 5), 10);
-        self.grid_to_path_1.insert((1, 6), 11);
-        self.grid_to_path_1.insert((1, 7), 12);
-        // Populate hash map for the second player
-        self.grid_to_path_2.insert((2, 0), 4);
-        self.grid_to_path_2.insert((2, 1), 3);
-        self.grid_to_path_2.insert((2, 2), 2);
-        self.grid_to_path_2.insert((2, 3), 1);
-        self.grid_to_path_2.insert((2, 4), 0);
-        self.grid_to_path_2.insert((2, 5), 15);
-        self.grid_to_path_2.insert((2, 6), 14);
-        self.grid_to_path_2.insert((2, 7), 13);
-        self.grid_to_path_2.insert((1, 0), 5);
-        self.grid_to_path_2.insert((1, 1), 6);
-        self.grid_to_path_2.insert((1, 2), 7);
-        self.grid_to_path_2.insert((1, 3), 8);
-        self.grid_to_path_2.insert((1, 4), 9);
-        self.grid_to_path_2.insert((1, 5), 10);
-        self.grid_to_path_2.insert((1, 6), 11);
-        self.grid_to_path_2.insert((1, 7), 12);
-
-        for (k, v) in self.grid_to_path_1.iter() {
-            self.path_to_grid_1.insert(*v, *k);
+    pub fn state(&self) -> GameState {
+        GameState {
+            player_1: self.player_1.clone(),
+            player_2: self.player_2.clone(),
+            active_player: self.active_player,
+            dice_roll: self.dice_roll,
+            legal_moves: self.legal_moves(),
+            winner: self.winner(),
         }
+    }
+
+    /// Position as text: both players' path cells as digits, the side to move
+    /// and the roll, e.g. `7000000000000000/7000000000000000 1 -`. Counts of
+    /// ten or more are written as letters, `a` for 10 and so on.
+    pub fn to_notation(&self) -> String {
+        let cells = |array: &[i8]| {
+            array
+                .iter()
+                .filter_map(|&n| std::char::from_digit(n as u32, 36))
+                .collect::<String>()
+        };
+        let player = match self.active_player {
+            Player::First => 1,
+            Player::Second => 2,
+        };
+        let roll = match self.dice_roll {
+            Some(roll) => roll.to_string(),
+            None => "-".to_string(),
+        };
+        format!(
+            "{}/{} {} {}",
+            cells(&self.player_1),
+            cells(&self.player_2),
+            player,
+            roll
+        )
+    }
+
+    /// Parses a position written by `to_notation` under the default ruleset.
+    pub fn from_notation(notation: &str) -> Option<Gameboard> {
+        Gameboard::from_notation_with_ruleset(Ruleset::default(), notation)
+    }
+
+    /// Parses a position written by `to_notation`.
+    pub fn from_notation_with_ruleset(ruleset: Ruleset, notation: &str) -> Option<Gameboard> {
+        let pieces = ruleset.pieces as i8;
+        let geometry = ruleset.track.geometry();
+        let cells = |text: &str| -> Option<Vec<i8>> {
+            let array = text
+                .chars()
+                .map(|c| c.to_digit(36).map(|n| n as i8))
+                .collect::<Option<Vec<i8>>>()?;
+            if array.len() != geometry.path_len() {
+                return None;
+            }
+            let on_board_ok = array[1..geometry.finish()].iter().all(|&n| n <= 1);
+            let total: i8 = array.iter().sum();
+            if on_board_ok && total == pieces {
+                Some(array)
+            } else {
+                None
+            }
+        };
 
-        for (k, v) in self.grid_to_path_2.iter() {
-            self.path_to_grid_2.insert(*v, *k);
+        let mut parts = notation.split_whitespace();
+        let mut players = parts.next()?.split('/');
+        let mut g = Gameboard::with_ruleset(ruleset);
+        g.player_1 = cells(players.next()?)?;
+        g.player_2 = cells(players.next()?)?;
+        g.active_player = match parts.next()? {
+            "1" => Player::First,
+            "2" => Player::Second,
+            _ => return None,
+        };
+        g.dice_roll = match parts.next()? {
+            "-" => None,
+            roll => match roll.parse() {
+                Ok(roll) if g.ruleset.dice.outcomes().iter().any(|&(r, _)| r == roll) => Some(roll),
+                _ => return None,
+            },
+        };
+        if players.next().is_some() || parts.next().is_some() {
+            return None;
         }
+        // Two pieces cannot share a square.
+        let occupied = |player, array: &[i8]| {
+            (1..geometry.finish())
+                .filter(|&i| array[i] == 1)
+                .filter_map(|i| geometry.grid_cell(player, i))
+                .collect::<Vec<_>>()
+        };
+        let first = occupied(Player::First, &g.player_1);
+        if occupied(Player::Second, &g.player_2)
+            .iter()
+            .any(|cell| first.contains(cell))
+        {
+            return None;
+        }
+        Some(g)
+    }
+
+    /// Active player's path cell at grid cell `(x, y)`, if it is on their track.
+    pub fn get_active_cell(&self, x: i8, y: i8) -> Option<usize> {
+        self.geometry.path_cell(self.active_player, (x, y))
     }
 }
@@ -1,26 +1,483 @@
 //! Gameboard controller.
 
-use piston::input::GenericEvent;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use piston::input::{GenericEvent, Key};
+use rand::rngs::StdRng;
+use rand::{FromEntropy, SeedableRng};
+
+use royal_game_of_ur::analysis::{self, Analysis};
+use royal_game_of_ur::assets;
+use royal_game_of_ur::bot::Bot;
+use royal_game_of_ur::clock::{self, Clock, Timeout};
+use royal_game_of_ur::logging::{GameEvent, GameLog};
+use royal_game_of_ur::luck::Accounting;
+use royal_game_of_ur::record::GameRecord;
+use royal_game_of_ur::report::Report;
+use royal_game_of_ur::save::SaveGame;
+use royal_game_of_ur::settings;
+use royal_game_of_ur::tablebase::Tablebase;
 
 use crate::gameboard::Player;
+use crate::menu_controller::GameSetup;
 use crate::Gameboard;
 
+/// Asset holding `ur_tablebase generate` output.
+pub const TABLEBASE_FILE: &str = "endgame.urtb";
+/// Data file of the game in progress, rewritten after every change.
+pub const SAVE_FILE: &str = "savegame.json";
+/// Data file recording the last finished game, for replays.
+pub const LAST_GAME_FILE: &str = "last_game.json";
+/// Data files holding the last finished game's blunder report, as Markdown
+/// and as JSON.
+pub const BLUNDERS_MARKDOWN_FILE: &str = "blunders.md";
+pub const BLUNDERS_JSON_FILE: &str = "blunders.json";
+/// Buttons on the victory screen, between "Roll" and "Pass": left edge and
+/// width relative to the board, then the top of the first and each height.
+pub const END_BUTTONS_LEFT: f64 = 130.;
+pub const END_BUTTONS_WIDTH: f64 = 220.;
+pub const END_BUTTONS_TOP: f64 = 244.;
+pub const END_BUTTON_HEIGHT: f64 = 38.;
+
 pub struct GameboardController {
     pub gameboard: Gameboard,
     pub cursor_pos: [f64; 2],
+    /// Evaluation of the current position for the analysis panel.
+    pub analysis: Analysis,
+    /// Notation of the position `analysis` belongs to.
+    analysed: String,
+    /// Endgame tablebase consulted by the analysis, if one was generated.
+    tablebase: Option<Tablebase>,
+    /// Every turn played so far.
+    pub record: GameRecord,
+    /// Blunder report, once the game is over.
+    pub report: Option<Report>,
+    /// Luck and skill per turn, once the game is over.
+    pub accounting: Option<Accounting>,
+    /// Whether the panel shows luck and skill graphs instead of the report.
+    pub show_graphs: bool,
+    /// Players, rules and dice of this game, reused for a rematch.
+    pub setup: GameSetup,
+    /// Games won this match, indexed by who played first in the first game.
+    pub score: [u32; 2],
+    /// Whether colours are swapped from the first game of the match.
+    pub swapped: bool,
+    /// Whether a rematch swaps colours.
+    pub swap_colours: bool,
+    /// Set when "Back to menu" is clicked, for the caller to act on.
+    pub back_to_menu: bool,
+    /// Path cell of the piece the keyboard would move.
+    pub focus: Option<usize>,
+    rng: StdRng,
+    /// Seconds a bot waits before each roll and move, so they can be followed.
+    pub bot_delay: f64,
+    /// Seconds the bot to move has waited so far.
+    bot_wait: f64,
+    /// Turn shown when stepping through a finished game instead of playing.
+    pub replay: Option<usize>,
+    /// Directory for a JSON log of each game, if any.
+    pub game_log_dir: Option<PathBuf>,
+    game_log: Option<GameLog>,
+    /// Both players' clocks, in a timed game.
+    pub clock: Option<Clock>,
 }
 
 impl GameboardController {
     pub fn new(gameboard: Gameboard) -> GameboardController {
+        GameboardController::with_tablebase(gameboard, load_tablebase())
+    }
+
+    /// Like `new`, with a tablebase already loaded.
+    fn with_tablebase(
+        gameboard: Gameboard,
+        tablebase: Option<Tablebase>,
+    ) -> GameboardController {
         GameboardController {
-            gameboard: gameboard,
+            analysis: analysis::analyse_with(
+                &gameboard,
+                analysis::DEFAULT_DEPTH,
+                tablebase.as_ref(),
+            ),
+            tablebase,
+            analysed: gameboard.to_notation(),
+            record: GameRecord::new(gameboard.ruleset.clone()),
+            report: None,
+            accounting: None,
+            show_graphs: false,
+            setup: GameSetup {
+                ruleset: gameboard.ruleset.clone(),
+                ..GameSetup::default()
+            },
+            score: [0, 0],
+            swapped: false,
+            swap_colours: false,
+            back_to_menu: false,
+            focus: None,
+            rng: StdRng::from_entropy(),
+            bot_delay: 0.6,
+            bot_wait: 0.0,
+            replay: None,
+            game_log_dir: None,
+            game_log: None,
+            clock: None,
+            gameboard,
             cursor_pos: [0.0; 2],
         }
     }
 
+    /// Starts a new game as chosen on the menu.
+    pub fn from_setup(setup: &GameSetup) -> GameboardController {
+        GameboardController::from_setup_with(setup, load_tablebase())
+    }
+
+    fn from_setup_with(setup: &GameSetup, tablebase: Option<Tablebase>) -> GameboardController {
+        let mut gameboard = Gameboard::with_ruleset(setup.ruleset.clone());
+        gameboard.active_player = setup.first;
+        let mut controller = GameboardController::with_tablebase(gameboard, tablebase);
+        controller.setup = setup.clone();
+        controller.clock = setup.time_control.map(Clock::new);
+        if let Some(seed) = setup.seed {
+            controller.rng = StdRng::seed_from_u64(seed);
+        }
+        controller
+    }
+
+    /// The next game of the match, continuing the same dice and keeping
+    /// the tablebase.
+    fn rematch(&mut self) -> GameboardController {
+        let mut setup = self.setup.clone();
+        if self.swap_colours {
+            setup.players.reverse();
+        }
+        let mut next = GameboardController::from_setup_with(&setup, self.tablebase.take());
+        next.rng = self.rng.clone();
+        next.bot_delay = self.bot_delay;
+        next.score = self.score;
+        next.swapped = self.swapped != self.swap_colours;
+        next.swap_colours = self.swap_colours;
+        next.game_log_dir = self.game_log_dir.clone();
+        next.start_log();
+        next
+    }
+
+    /// Logs the start of the game, and opens a game log in `game_log_dir`
+    /// if it is set. Replays are not logged.
+    pub fn start_log(&mut self) {
+        if self.replay.is_some() {
+            return;
+        }
+        if let Some(ref dir) = self.game_log_dir {
+            match GameLog::create(dir) {
+                Ok(log) => {
+                    info!("Logging the game to {}", log.path().display());
+                    self.game_log = Some(log);
+                }
+                Err(e) => warn!("Could not create a game log in {}: {}", dir.display(), e),
+            }
+        }
+        let event = GameEvent::start(&self.gameboard);
+        self.emit(event);
+    }
+
+    /// Logs `event`, and writes it to the game log if there is one.
+    fn emit(&mut self, event: GameEvent) {
+        event.log();
+        let failed = match self.game_log {
+            Some(ref mut log) => log.write(&event).err(),
+            None => None,
+        };
+        if let Some(e) = failed {
+            warn!("Could not write the game log, closing it: {}", e);
+            self.game_log = None;
+        }
+    }
+
+    fn roll(&mut self) {
+        let player = self.gameboard.active_player;
+        let roll = self.gameboard.roll(&mut self.rng);
+        self.emit(GameEvent::Roll { player, roll });
+    }
+
+    /// Moves the piece on `cell` if it can move.
+    fn play(&mut self, cell: usize) {
+        let player = self.gameboard.active_player;
+        let event = GameEvent::play(&self.gameboard, cell);
+        if self.record.play(&mut self.gameboard, cell) {
+            self.emit(event);
+            self.end_move(player);
+        } else {
+            let input = format!("the piece on {} cannot move", cell);
+            self.emit(GameEvent::Invalid { player, input });
+        }
+    }
+
+    fn pass(&mut self) {
+        let player = self.gameboard.active_player;
+        self.record.pass(&mut self.gameboard);
+        self.emit(GameEvent::Pass { player });
+        self.end_move(player);
+    }
+
+    fn end_move(&mut self, player: Player) {
+        if let Some(ref mut clock) = self.clock {
+            clock.end_move(player);
+        }
+    }
+
+    /// Runs the clock of the player to move. When their time runs out,
+    /// moves for them or forfeits the game, as the time control says.
+    fn run_clock(&mut self, dt: f64) {
+        if self.replay.is_some() || self.is_finished() {
+            return;
+        }
+        let player = self.gameboard.active_player;
+        let timeout = match self.clock {
+            Some(ref mut clock) => {
+                if !clock.tick(player, dt) {
+                    return;
+                }
+                clock.control.timeout
+            }
+            None => return,
+        };
+        let forfeit = timeout == Timeout::Forfeit;
+        self.emit(GameEvent::Timeout { player, forfeit });
+        if forfeit {
+            if let Some(ref mut clock) = self.clock {
+                clock.forfeited = Some(player);
+            }
+            self.autosave();
+        } else {
+            if self.gameboard.dice_roll.is_none() {
+                self.roll();
+            }
+            let tablebase = self.tablebase.as_ref();
+            match clock::timeout_move(&self.gameboard, timeout, tablebase, &mut self.rng) {
+                Some(cell) => self.play(cell),
+                None => self.pass(),
+            }
+        }
+        self.refresh_analysis();
+    }
+
+    /// The winner, on the board or on time.
+    pub fn winner(&self) -> Option<Player> {
+        self.gameboard
+            .winner()
+            .or_else(|| self.clock.as_ref().and_then(Clock::winner))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.winner().is_some()
+    }
+
+    /// Games won this match by whoever plays `player` now.
+    pub fn wins(&self, player: Player) -> u32 {
+        self.score[self.seat(player)]
+    }
+
+    /// Index into `score` of whoever plays `player` now.
+    fn seat(&self, player: Player) -> usize {
+        match (player, self.swapped) {
+            (Player::First, false) | (Player::Second, true) => 0,
+            _ => 1,
+        }
+    }
+
+    /// Steps through `record` from its first turn. Returns `None` for an
+    /// empty or unreadable record.
+    pub fn replay(record: GameRecord) -> Option<GameboardController> {
+        let mut controller = GameboardController::new(record.position(0)?);
+        controller.record = record;
+        controller.replay = Some(0);
+        Some(controller)
+    }
+
+    /// Bot seated at the side to move, if any.
+    fn active_bot(&self) -> Option<Bot> {
+        match self.gameboard.active_player {
+            Player::First => self.setup.players[0],
+            Player::Second => self.setup.players[1],
+        }
+    }
+
+    /// Lets a bot to move roll or play once it has waited `bot_delay`.
+    pub fn update(&mut self, dt: f64) {
+        self.run_clock(dt);
+        let bot = match self.active_bot() {
+            Some(bot) if self.replay.is_none() && !self.is_finished() => bot,
+            _ => return,
+        };
+        self.bot_wait += dt;
+        if self.bot_wait < self.bot_delay {
+            return;
+        }
+        self.bot_wait = 0.0;
+        if self.gameboard.dice_roll.is_none() {
+            self.roll();
+        } else {
+            match bot.choose(&self.gameboard, self.tablebase.as_ref()) {
+                Some(cell) => self.play(cell),
+                None => self.pass(),
+            }
+        }
+        self.refresh_analysis();
+    }
+
+    /// Whether a human may act now.
+    fn human_to_move(&self) -> bool {
+        self.replay.is_none() && self.active_bot().is_none() && !self.is_finished()
+    }
+
+    /// Shows turn `turn` of the replayed game, or the final position after
+    /// the last one.
+    fn show_turn(&mut self, turn: usize) {
+        if let Some(gameboard) = self.record.position(turn) {
+            self.gameboard = gameboard;
+            self.replay = Some(turn);
+        }
+    }
+
+    /// Saves the game in progress, clocks included, or removes the save
+    /// once the game is over so that "Continue" cannot resume it.
+    fn autosave(&self) {
+        if self.is_finished() {
+            let path = settings::data_path(SAVE_FILE);
+            match fs::remove_file(&path) {
+                Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
+                    warn!("Could not remove {}: {}", path.display(), e)
+                }
+                _ => {}
+            }
+            return;
+        }
+        let save = SaveGame {
+            clock: self.clock.clone(),
+            ..SaveGame::new(&self.gameboard)
+        };
+        if let Err(e) = save.write(data_file(SAVE_FILE)) {
+            warn!("Could not save game: {}", e);
+        }
+    }
+
+    /// Re-evaluates the position if it changed since the last event.
+    fn refresh_analysis(&mut self) {
+        let notation = self.gameboard.to_notation();
+        if notation != self.analysed {
+            self.analysis = analysis::analyse_with(
+                &self.gameboard,
+                analysis::DEFAULT_DEPTH,
+                self.tablebase.as_ref(),
+            );
+            self.analysed = notation;
+            self.focus = if self.human_to_move() {
+                self.gameboard.legal_moves().first().cloned()
+            } else {
+                None
+            };
+            if self.replay.is_none() {
+                self.autosave();
+            }
+        }
+        if self.is_finished() && self.report.is_none() {
+            if let (None, Some(winner)) = (self.replay, self.winner()) {
+                self.emit(GameEvent::End { winner });
+                self.game_log = None;
+                if let Err(e) = self.record.write(data_file(LAST_GAME_FILE)) {
+                    warn!("Could not write game record: {}", e);
+                }
+                let seat = self.seat(winner);
+                self.score[seat] += 1;
+            }
+            let report = Report::new(&self.record, analysis::DEFAULT_DEPTH);
+            if let Err(e) = report
+                .write_markdown(data_file(BLUNDERS_MARKDOWN_FILE))
+                .and_then(|_| report.write_json(data_file(BLUNDERS_JSON_FILE)))
+            {
+                warn!("Could not write blunder report: {}", e);
+            }
+            self.report = Some(report);
+            self.accounting = Some(Accounting::new(&self.record, analysis::DEFAULT_DEPTH));
+        }
+    }
+
+    /// Handles a key: space rolls, arrows or number keys pick a piece,
+    /// enter moves it and P passes. Arrows step through a replay, and on the
+    /// victory screen enter starts a rematch and M goes back to the menu.
+    fn key_press(&mut self, key: Key) {
+        if let Some(turn) = self.replay {
+            match key {
+                Key::Left | Key::Up => self.show_turn(turn.saturating_sub(1)),
+                Key::Right | Key::Down => self.show_turn(turn + 1),
+                Key::M if self.gameboard.is_finished() => self.back_to_menu = true,
+                _ => {}
+            }
+            return;
+        }
+        if self.is_finished() {
+            match key {
+                Key::Return => *self = self.rematch(),
+                Key::S => self.swap_colours = !self.swap_colours,
+                Key::M => self.back_to_menu = true,
+                _ => {}
+            }
+            return;
+        }
+        if !self.human_to_move() {
+            return;
+        }
+        let moves = self.gameboard.legal_moves();
+        let focused = self
+            .focus
+            .and_then(|cell| moves.iter().position(|&m| m == cell));
+        match key {
+            Key::Space if self.gameboard.dice_roll.is_none() => self.roll(),
+            Key::Right | Key::Down if !moves.is_empty() => {
+                let next = focused.map_or(0, |i| (i + 1) % moves.len());
+                self.focus = Some(moves[next]);
+            }
+            Key::Left | Key::Up if !moves.is_empty() => {
+                let previous =
+                    focused.map_or(moves.len() - 1, |i| (i + moves.len() - 1) % moves.len());
+                self.focus = Some(moves[previous]);
+            }
+            Key::Return => {
+                if let Some(cell) = self.focus {
+                    self.play(cell);
+                }
+            }
+            Key::P => self.pass(),
+            _ => {
+                // Number keys pick the n-th movable piece, counted from the start.
+                let n = match key {
+                    Key::D1 => 1,
+                    Key::D2 => 2,
+                    Key::D3 => 3,
+                    Key::D4 => 4,
+                    Key::D5 => 5,
+                    Key::D6 => 6,
+                    Key::D7 => 7,
+                    Key::D8 => 8,
+                    Key::D9 => 9,
+                    _ => return,
+                };
+                if let Some(&cell) = moves.get(n - 1) {
+                    self.focus = Some(cell);
+                }
+            }
+        }
+    }
+
     pub fn event<E: GenericEvent>(&mut self, pos: [f64; 2], size: [f64; 2], e: &E) {
         use piston::input::{Button, MouseButton};
 
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            self.key_press(key);
+            self.refresh_analysis();
+        }
+
         if let Some(pos) = e.mouse_cursor_args() {
             self.cursor_pos = pos;
         }
@@ -29,54 +486,84 @@ impl GameboardController {
             // Find coordinates relative to upper left corner.
             let x = self.cursor_pos[0] - pos[0];
             let y = self.cursor_pos[1] - pos[1];
+            // Humans can only act on their own turns.
+            let playing = self.human_to_move();
 
             // Check that coordinates are inside board boundaries.
-            if !self.gameboard.is_finished()
-                && x >= 0.0
-                && x <= size[0]
-                && y >= 0.0
-                && y <= size[1]
-                && self.gameboard.dice_roll != None
+            if playing
+                && (0.0..=size[0]).contains(&x)
+                && (0.0..=size[1]).contains(&y)
+                && self.gameboard.dice_roll.is_some()
             {
                 let cell_x = (x / 60.) as usize;
                 let cell_y = (y / 60.) as usize;
-                println!("{}, {}", cell_x, cell_y);
-
-                if match self.gameboard.active_player {
-                    Player::First => self
-                        .gameboard
-                        .grid_to_path_1
-                        .contains_key(&(cell_y as i8, cell_x as i8)),
-                    Player::Second => self
-                        .gameboard
-                        .grid_to_path_2
-                        .contains_key(&(cell_y as i8, cell_x as i8)),
-                } {
-                    let cell = self.gameboard.get_active_cell(cell_y as i8, cell_x as i8);
-                    self.gameboard._move(cell);
+                trace!("Clicked cell {}, {}", cell_x, cell_y);
+
+                match self.gameboard.get_active_cell(cell_y as i8, cell_x as i8) {
+                    Some(cell) => self.play(cell),
+                    None => {
+                        let player = self.gameboard.active_player;
+                        let input = format!("no piece to move at {}, {}", cell_x, cell_y);
+                        self.emit(GameEvent::Invalid { player, input });
+                    }
                 }
 
             // Check "Roll" button clicked.
-            } else if !self.gameboard.is_finished()
-                && x >= 0.
-                && x <= 60. * 2.
-                && y <= (60. * 6.)
-                && y >= (60. * 4.)
-                && self.gameboard.dice_roll == None
+            } else if playing
+                && (0.0..=60. * 2.).contains(&x)
+                && (60. * 4.0..=60. * 6.).contains(&y)
+                && self.gameboard.dice_roll.is_none()
             {
-                self.gameboard.roll();
+                self.roll();
             }
             // Check "Pass" button clicked.
-            else if !self.gameboard.is_finished()
-                && x >= 360.
-                && x <= 480.
-                && y <= (60. * 6.)
-                && y >= (60. * 4.)
+            else if playing && (360.0..=480.).contains(&x) && (60. * 4.0..=60. * 6.).contains(&y)
+            {
+                self.pass();
+            }
+            // Check the victory screen's buttons clicked.
+            else if self.is_finished()
+                && (END_BUTTONS_LEFT..=END_BUTTONS_LEFT + END_BUTTONS_WIDTH).contains(&x)
+                && (END_BUTTONS_TOP..END_BUTTONS_TOP + 3. * END_BUTTON_HEIGHT).contains(&y)
             {
-                self.gameboard.pass_turn();
+                match ((y - END_BUTTONS_TOP) / END_BUTTON_HEIGHT) as usize {
+                    0 if self.replay.is_none() => *self = self.rematch(),
+                    1 if self.replay.is_none() => self.swap_colours = !self.swap_colours,
+                    2 => self.back_to_menu = true,
+                    _ => {}
+                }
+            }
+            // In a replay "Roll" and "Pass" step back and forward.
+            else if let (Some(turn), true) = (self.replay, (60. * 4.0..=60. * 6.).contains(&y)) {
+                if (0.0..=60. * 2.).contains(&x) {
+                    self.show_turn(turn.saturating_sub(1));
+                } else if (360.0..=480.).contains(&x) {
+                    self.show_turn(turn + 1);
+                }
+            }
+            // Check the post-game panel clicked.
+            else if self.report.is_some() && x > size[0] {
+                self.show_graphs = !self.show_graphs;
             } else {
-                println!("{}, {}", x, y)
+                trace!("Click at {}, {} hit nothing", x, y)
             }
+            self.refresh_analysis();
+        }
+    }
+}
+
+/// Reads the endgame tablebase, if one was generated.
+pub fn load_tablebase() -> Option<Tablebase> {
+    assets::find(TABLEBASE_FILE).and_then(|path| Tablebase::read(path).ok())
+}
+
+/// `settings::data_path(name)`, creating its directory if needed.
+fn data_file(name: &str) -> PathBuf {
+    let path = settings::data_path(name);
+    if let Some(dir) = path.parent() {
+        if let Err(e) = fs::create_dir_all(dir) {
+            warn!("Could not create {}: {}", dir.display(), e);
         }
     }
+    path
 }
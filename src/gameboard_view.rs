@@ -5,7 +5,15 @@ use graphics::types::Color;
 use graphics::*;
 use graphics::{Context, Graphics};
 
+use royal_game_of_ur::clock;
+use royal_game_of_ur::dice::Dice;
+use royal_game_of_ur::theme::{PieceShape, Theme};
+
 use crate::gameboard::Player;
+use crate::gameboard_controller::{
+    END_BUTTONS_LEFT, END_BUTTONS_TOP, END_BUTTONS_WIDTH, END_BUTTON_HEIGHT,
+};
+use crate::sprites::Sprites;
 use crate::GameboardController;
 
 pub struct GameboardViewSettings {
@@ -14,6 +22,8 @@ pub struct GameboardViewSettings {
     /// Size of gameboard along horizontal and vertical edge.
     pub size_x: f64,
     pub size_y: f64,
+    /// Window color behind the board.
+    pub window_color: Color,
     /// Background color.
     pub background_color: Color,
     /// Border color.
@@ -38,27 +48,51 @@ pub struct GameboardViewSettings {
     pub player_1_board_color: Color,
     /// Player 2 board Color.
     pub player_2_board_color: Color,
+    /// Rosette marker color.
+    pub rosette_color: Color,
+    /// Outline of the piece chosen with the keyboard.
+    pub focus_color: Color,
+    /// Player 1 piece shape.
+    pub player_1_shape: PieceShape,
+    /// Player 2 piece shape.
+    pub player_2_shape: PieceShape,
+}
+
+impl Default for GameboardViewSettings {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameboardViewSettings {
-    /// Creates new gameboard view settings.
+    /// Creates new gameboard view settings in the default theme.
     pub fn new() -> GameboardViewSettings {
+        GameboardViewSettings::with_theme(&Theme::default())
+    }
+
+    /// Creates new gameboard view settings with `theme`'s colours and shapes.
+    pub fn with_theme(theme: &Theme) -> GameboardViewSettings {
         GameboardViewSettings {
             position: [10.0, 60.0],
             size_x: 60.0 * 8.0,
             size_y: 60.0 * 3.0,
-            background_color: [0.8, 0.8, 0.8, 1.0],
-            border_color: [0.0, 0.0, 0.2, 1.0],
-            board_edge_color: [0.0, 0.0, 0.2, 1.0],
-            section_edge_color: [0.0, 0.0, 0.2, 1.0],
-            cell_edge_color: [0.0, 0.0, 0.2, 1.0],
+            window_color: theme.window_color,
+            background_color: theme.background_color,
+            border_color: theme.border_color,
+            board_edge_color: theme.board_edge_color,
+            section_edge_color: theme.section_edge_color,
+            cell_edge_color: theme.cell_edge_color,
             board_edge_radius: 2.0,
             section_edge_radius: 1.0,
             cell_edge_radius: 1.0,
-            player_1_color: [0.8, 0.1, 0.1, 1.0],
-            player_2_color: [0.1, 0.1, 0.8, 1.0],
-            player_1_board_color: [1.0, 0.8, 0.8, 1.0],
-            player_2_board_color: [0.8, 0.8, 1.0, 1.0],
+            player_1_color: theme.player_1_color,
+            player_2_color: theme.player_2_color,
+            player_1_board_color: theme.player_1_board_color,
+            player_2_board_color: theme.player_2_board_color,
+            rosette_color: theme.rosette_color,
+            focus_color: theme.focus_color,
+            player_1_shape: theme.player_1_shape,
+            player_2_shape: theme.player_2_shape,
         }
     }
 }
@@ -72,13 +106,98 @@ pub struct GameboardView {
 impl GameboardView {
     /// Creates a new gameboard view.
     pub fn new(settings: GameboardViewSettings) -> GameboardView {
-        GameboardView { settings: settings }
+        GameboardView { settings }
+    }
+
+    /// Draws one of `player`'s checkers as their sprite or in their shape.
+    /// `pos` is where the "•" glyph starts, which puts its dot about 18 right
+    /// and 70 above.
+    fn draw_checker<G: Graphics, C>(
+        &self,
+        player: Player,
+        pos: [f64; 2],
+        sprites: &Sprites<G::Texture>,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        let settings = &self.settings;
+        let (color, shape, sprite) = match player {
+            Player::First => (
+                settings.player_1_color,
+                settings.player_1_shape,
+                &sprites.pieces[0],
+            ),
+            Player::Second => (
+                settings.player_2_color,
+                settings.player_2_shape,
+                &sprites.pieces[1],
+            ),
+        };
+        let (x, y) = (pos[0] + 18., pos[1] - 70.);
+        if let Some(texture) = sprite {
+            Image::new().rect([x - 22., y - 22., 44., 44.]).draw(
+                texture,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+            return;
+        }
+        let outline = Line::new(settings.board_edge_color, 1.);
+        let polygon = |points: &[[f64; 2]], g: &mut G| {
+            Polygon::new(color).draw(points, &c.draw_state, c.transform, g);
+            for (i, &a) in points.iter().enumerate() {
+                let b = points[(i + 1) % points.len()];
+                outline.draw([a[0], a[1], b[0], b[1]], &c.draw_state, c.transform, g);
+            }
+        };
+        match shape {
+            PieceShape::Dot => {
+                if let Ok(character) = glyphs.character(90, '•') {
+                    let ch_x = pos[0] + character.left();
+                    let ch_y = pos[1] - character.top();
+                    text::Text::new_color(color, 90)
+                        .draw(
+                            "•",
+                            glyphs,
+                            &c.draw_state,
+                            c.transform.trans(ch_x, ch_y),
+                            g,
+                        )
+                        .ok();
+                }
+            }
+            PieceShape::Square => polygon(
+                &[[x - 12., y - 12.], [x + 12., y - 12.], [x + 12., y + 12.], [x - 12., y + 12.]],
+                g,
+            ),
+            PieceShape::Diamond => polygon(
+                &[[x, y - 16.], [x + 16., y], [x, y + 16.], [x - 16., y]],
+                g,
+            ),
+            PieceShape::Triangle => polygon(
+                &[[x, y - 15.], [x + 15., y + 12.], [x - 15., y + 12.]],
+                g,
+            ),
+            PieceShape::Ring => {
+                Ellipse::new_border(color, 4.).draw(
+                    [x - 13., y - 13., 26., 26.],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+        }
     }
 
     /// Draw gameboard.
     pub fn draw<G: Graphics, C>(
         &self,
         controller: &GameboardController,
+        sprites: &Sprites<G::Texture>,
         glyphs: &mut C,
         c: &Context,
         g: &mut G,
@@ -87,7 +206,7 @@ impl GameboardView {
     {
         //use graphics::{Image, Line, Rectangle, Transformed};
 
-        let ref settings = self.settings;
+        let settings = &self.settings;
         let board_rect = [
             settings.position[0],
             settings.position[1],
@@ -97,7 +216,40 @@ impl GameboardView {
         // Draw board background.
         Rectangle::new(settings.background_color).draw(board_rect, &c.draw_state, c.transform, g);
 
-        let (player_rect, player_color) = match controller.gameboard.active_player {
+        // Draw tile sprites where there are any: rosettes, the shared lane's
+        // eyes and plain tiles elsewhere.
+        let geometry = &controller.gameboard.geometry;
+        let rosettes = &controller.gameboard.ruleset.rosettes;
+        let mut tiled = false;
+        for row in 0..geometry.size.0 {
+            for column in 0..geometry.size.1 {
+                if !geometry.is_square((row, column)) {
+                    continue;
+                }
+                let tile = sprites.tile.as_ref();
+                let sprite = if rosettes.contains(&(row, column)) {
+                    sprites.rosette.as_ref().or(tile)
+                } else if row == 1 {
+                    sprites.eyes.as_ref().or(tile)
+                } else {
+                    tile
+                };
+                if let Some(texture) = sprite {
+                    let rect = [
+                        settings.position[0] + f64::from(column) * 60.,
+                        settings.position[1] + f64::from(row) * 60.,
+                        60.,
+                        60.,
+                    ];
+                    Image::new()
+                        .rect(rect)
+                        .draw(texture, &c.draw_state, c.transform, g);
+                    tiled = true;
+                }
+            }
+        }
+
+        let (player_rect, mut player_color) = match controller.gameboard.active_player {
             Player::First => (
                 [
                     settings.position[0],
@@ -118,21 +270,53 @@ impl GameboardView {
             ),
         };
 
+        // Let the tiles show through the turn indicator.
+        if tiled {
+            player_color[3] = 0.35;
+        }
         Rectangle::new(player_color).draw(player_rect, &c.draw_state, c.transform, g);
+        // Outline the turn indicator when the same player moves again.
+        if controller.gameboard.extra_turn {
+            Rectangle::new_border(settings.rosette_color, 3.).draw(
+                player_rect,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
 
         // Draw empty space.
-        let mut black_rect = [
-            settings.position[0] + settings.size_x / 2.0,
-            settings.position[1],
-            settings.size_x / 4.0,
-            settings.size_y / 3.0,
-        ];
-
-        Rectangle::new(settings.board_edge_color).draw(black_rect, &c.draw_state, c.transform, g);
-
-        black_rect[1] = black_rect[1] + settings.size_y * (2.0 / 3.0);
+        for row in 0..geometry.size.0 {
+            for column in 0..geometry.size.1 {
+                if !geometry.is_square((row, column)) {
+                    let black_rect = [
+                        settings.position[0] + f64::from(column) * 60.,
+                        settings.position[1] + f64::from(row) * 60.,
+                        60.,
+                        60.,
+                    ];
+                    Rectangle::new(settings.board_edge_color).draw(
+                        black_rect,
+                        &c.draw_state,
+                        c.transform,
+                        g,
+                    );
+                }
+            }
+        }
 
-        Rectangle::new(settings.board_edge_color).draw(black_rect, &c.draw_state, c.transform, g);
+        // Draw rosettes, unless they have a sprite.
+        let rosettes = if sprites.rosette.is_some() {
+            &[][..]
+        } else {
+            &rosettes[..]
+        };
+        for &(row, column) in rosettes {
+            let x = settings.position[0] + f64::from(column) * 60. + 30.;
+            let y = settings.position[1] + f64::from(row) * 60. + 30.;
+            let diamond = [[x, y - 15.], [x + 15., y], [x, y + 15.], [x - 15., y]];
+            Polygon::new(settings.rosette_color).draw(&diamond, &c.draw_state, c.transform, g);
+        }
 
         // Draw horizontal lines.
         let section_edge = Line::new(settings.section_edge_color, settings.section_edge_radius);
@@ -165,134 +349,81 @@ impl GameboardView {
             g,
         );
 
-        // Draw first player's checkers.
+        // Draw each player's checkers: on the board, unused and finished.
         let cell_size = 60.0;
-        for i in 1..15 {
-            if controller.gameboard.player_1[i] != 0 {
+        // Reserve and finished piles share the space above or below the
+        // board; tighten the spacing when there are more pieces than seven.
+        let pieces = f64::from(controller.gameboard.ruleset.pieces);
+        let spacing = (210. / pieces).min(30.);
+        for &(player, pile_y) in &[(Player::First, 100.), (Player::Second, 340.)] {
+            let checkers = match player {
+                Player::First => &controller.gameboard.player_1,
+                Player::Second => &controller.gameboard.player_2,
+            };
+            let on_board = (1..geometry.finish()).filter(|&i| checkers[i] != 0);
+            for (row, column) in on_board.filter_map(|i| geometry.grid_cell(player, i)) {
                 let pos = [
-                    settings.position[0]
-                        + controller.gameboard.path_to_grid_1[&(i as i8)].1 as f64 * cell_size
-                        + 12.,
-                    settings.position[1]
-                        + controller.gameboard.path_to_grid_1[&(i as i8)].0 as f64 * cell_size
-                        + 100.,
+                    settings.position[0] + f64::from(column) * cell_size + 12.,
+                    settings.position[1] + f64::from(row) * cell_size + 100.,
                 ];
-                if let Ok(character) = glyphs.character(90, '•') {
-                    let ch_x = pos[0] as f64 + character.left();
-                    let ch_y = pos[1] as f64 - character.top();
-                    text::Text::new_color(settings.player_1_color, 90)
-                        .draw(
-                            "•",
-                            glyphs,
-                            &c.draw_state,
-                            c.transform.trans(ch_x, ch_y),
-                            g,
-                        )
-                        .ok();
-                }
-            }
-        }
-
-        // Draw unused checkers.
-        for i in 0..controller.gameboard.player_1[0] as usize {
-            let pos = [22. + (i as f64 * 30.), 100.];
-
-            if let Ok(character) = glyphs.character(90, '•') {
-                let ch_x = pos[0] as f64 + character.left();
-                let ch_y = pos[1] as f64 - character.top();
-                text::Text::new_color(settings.player_1_color, 90)
-                    .draw(
-                        "•",
-                        glyphs,
-                        &c.draw_state,
-                        c.transform.trans(ch_x, ch_y),
-                        g,
-                    )
-                    .ok();
+                self.draw_checker(player, pos, sprites, glyphs, c, g);
             }
-        }
-
-        for i in 0..controller.gameboard.player_1[15] as usize {
-            let pos = [22. + 60. * 7. - (i as f64 * 30.), 100.];
-
-            if let Ok(character) = glyphs.character(90, '•') {
-                let ch_x = pos[0] as f64 + character.left();
-                let ch_y = pos[1] as f64 - character.top();
-                text::Text::new_color(settings.player_1_color, 90)
-                    .draw(
-                        "•",
-                        glyphs,
-                        &c.draw_state,
-                        c.transform.trans(ch_x, ch_y),
-                        g,
-                    )
-                    .ok();
-            }
-        }
-
-        // Draw second player's checkers.
-        for i in 1..15 {
-            if controller.gameboard.player_2[i] == 1 {
-                let pos = [
-                    settings.position[0]
-                        + controller.gameboard.path_to_grid_2[&(i as i8)].1 as f64 * cell_size
-                        + 12.,
-                    settings.position[1]
-                        + controller.gameboard.path_to_grid_2[&(i as i8)].0 as f64 * cell_size
-                        + 100.,
-                ];
-                if let Ok(character) = glyphs.character(90, '•') {
-                    let ch_x = pos[0] as f64 + character.left();
-                    let ch_y = pos[1] as f64 - character.top();
-                    text::Text::new_color(settings.player_2_color, 90)
-                        .draw(
-                            "•",
-                            glyphs,
-                            &c.draw_state,
-                            c.transform.trans(ch_x, ch_y),
-                            g,
-                        )
-                        .ok();
-                }
+            for i in 0..checkers[0] as usize {
+                let pos = [22. + (i as f64 * spacing), pile_y];
+                self.draw_checker(player, pos, sprites, glyphs, c, g);
             }
-        }
-
-        // Draw unused checkers.
-        for i in 0..controller.gameboard.player_2[0] as usize {
-            let pos = [22. + (i as f64 * 30.), 340.];
-            if let Ok(character) = glyphs.character(90, '•') {
-                let ch_x = pos[0] as f64 + character.left();
-                let ch_y = pos[1] as f64 - character.top();
-                text::Text::new_color(settings.player_2_color, 90)
-                    .draw(
-                        "•",
-                        glyphs,
-                        &c.draw_state,
-                        c.transform.trans(ch_x, ch_y),
-                        g,
-                    )
-                    .ok();
+            for i in 0..checkers[geometry.finish()] as usize {
+                let pos = [22. + 60. * 7. - (i as f64 * spacing), pile_y];
+                self.draw_checker(player, pos, sprites, glyphs, c, g);
             }
         }
 
-        for i in 0..controller.gameboard.player_2[15] as usize {
-            let pos = [22. + 60. * 7. - (i as f64 * 30.), 340.];
-            if let Ok(character) = glyphs.character(90, '•') {
-                let ch_x = pos[0] as f64 + character.left();
-                let ch_y = pos[1] as f64 - character.top();
-                text::Text::new_color(settings.player_2_color, 90)
-                    .draw(
-                        "•",
-                        glyphs,
-                        &c.draw_state,
-                        c.transform.trans(ch_x, ch_y),
-                        g,
-                    )
-                    .ok();
+        // Outline the piece the keyboard would move, and where it would land.
+        if let (Some(cell), Some(roll)) = (controller.focus, controller.gameboard.dice_roll) {
+            let player = controller.gameboard.active_player;
+            let square = |(row, column): (i8, i8)| {
+                [
+                    settings.position[0] + f64::from(column) * 60.,
+                    settings.position[1] + f64::from(row) * 60.,
+                    60.,
+                    60.,
+                ]
+            };
+            // Reserve pieces wait off the board, above or below it.
+            let from = match (cell, player) {
+                (0, Player::First) => [settings.position[0], settings.position[1] - 60., 60., 60.],
+                (0, Player::Second) => [
+                    settings.position[0],
+                    settings.position[1] + settings.size_y,
+                    60.,
+                    60.,
+                ],
+                _ => geometry.grid_cell(player, cell).map_or([0.; 4], square),
+            };
+            Rectangle::new_border(settings.focus_color, 3.).draw(
+                from,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+            let target = cell + roll as usize;
+            let to = geometry.grid_cell(player, target);
+            if let (true, Some(to)) = (target < geometry.finish(), to) {
+                Rectangle::new_border(settings.focus_color, 1.5).draw(
+                    square(to),
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
             }
         }
 
-        // Draw Buttons.
+        // Draw Buttons. A replay steps through the game with them instead.
+        let (roll_label, pass_label) = if controller.replay.is_some() {
+            ("Prev", "Next")
+        } else {
+            ("Roll", "Pass")
+        };
         // "Roll" Button.
         let roll_button_rect = [
             settings.position[0],
@@ -304,7 +435,7 @@ impl GameboardView {
         let button_color;
         let text_color;
 
-        if controller.gameboard.dice_roll == None {
+        if controller.gameboard.dice_roll.is_none() || controller.replay.is_some() {
             button_color = settings.background_color;
             text_color = settings.board_edge_color;
         } else {
@@ -325,7 +456,7 @@ impl GameboardView {
 
         text::Text::new_color(text_color, 60)
             .draw(
-                "Roll",
+                roll_label,
                 glyphs,
                 &c.draw_state,
                 c.transform.trans(16., 60. * 6.35),
@@ -358,7 +489,7 @@ impl GameboardView {
 
         text::Text::new_color(settings.board_edge_color, 60)
             .draw(
-                "Pass",
+                pass_label,
                 glyphs,
                 &c.draw_state,
                 c.transform.trans(15. + 60. * 5.9, 60. * 6.35),
@@ -366,35 +497,326 @@ impl GameboardView {
             )
             .ok();
 
-        // Draw roll's result.
-        match controller.gameboard.dice_roll {
-            Some(x) => {
-                text::Text::new_color(settings.board_edge_color, 120)
+        // Draw the replayed turn, or the roll's result.
+        let finished = controller.is_finished();
+        if let (Some(turn), false) = (controller.replay, finished) {
+            text::Text::new_color(settings.board_edge_color, 30)
+                .draw(
+                    &format!("{} / {}", turn, controller.record.turns.len()),
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(60. * 2.9, 60. * 6.2),
+                    g,
+                )
+                .ok();
+        } else if let (Some(x), Dice::Binary(count), Some(marked), Some(blank)) = (
+            controller.gameboard.dice_roll,
+            &controller.gameboard.ruleset.dice,
+            &sprites.die_marked,
+            &sprites.die_blank,
+        ) {
+            // One die per coin of the roll, the first `x` marked, and the
+            // total beneath them.
+            let size = (220. / f64::from(*count)).min(44.);
+            for i in 0..*count {
+                let die = if (i as i8) < x { marked } else { blank };
+                let rect = [
+                    settings.position[0] + 130. + f64::from(i) * size,
+                    settings.position[1] + 250.,
+                    size - 4.,
+                    size - 4.,
+                ];
+                Image::new()
+                    .rect(rect)
+                    .draw(die, &c.draw_state, c.transform, g);
+            }
+            text::Text::new_color(settings.board_edge_color, 40)
+                .draw(
+                    &format!("{}", x),
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(10. + 60. * 3.7, 60. * 6.6),
+                    g,
+                )
+                .ok();
+        } else if let Some(x) = controller.gameboard.dice_roll {
+            text::Text::new_color(settings.board_edge_color, 120)
+                .draw(
+                    &format!("{}", x),
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(10. + 60. * 3.5, 60. * 6.6),
+                    g,
+                )
+                .ok();
+        } else if controller.gameboard.extra_turn && !finished {
+            let color = match controller.gameboard.active_player {
+                Player::First => settings.player_1_color,
+                Player::Second => settings.player_2_color,
+            };
+            text::Text::new_color(color, 40)
+                .draw(
+                    "Again!",
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(60. * 2.6, 60. * 6.2),
+                    g,
+                )
+                .ok();
+        }
+
+        // Draw the clocks above the board, the running one larger.
+        if let (Some(clock), None) = (&controller.clock, controller.replay) {
+            for &(player, name, color, x) in &[
+                (Player::First, "First", settings.player_1_color, 0),
+                (Player::Second, "Second", settings.player_2_color, 1),
+            ] {
+                let running = !finished && controller.gameboard.active_player == player;
+                let left = if running {
+                    clock.time_left(player)
+                } else {
+                    // The game clock, or else a fresh move's time.
+                    let game = clock.control.game.map(|_| clock.remaining[x]);
+                    game.or(clock.control.per_move)
+                };
+                let time = left.map_or("-".to_string(), clock::format_time);
+                text::Text::new_color(color, if running { 26 } else { 20 })
+                    .draw(
+                        &format!("{}: {}", name, time),
+                        glyphs,
+                        &c.draw_state,
+                        c.transform.trans(settings.position[0] + x as f64 * 240., 40.),
+                        g,
+                    )
+                    .ok();
+            }
+        }
+
+        // Draw analysis panel beside the board, or the blunder report once the
+        // game is over.
+        let panel_x = settings.position[0] + settings.size_x + 20.;
+        // Match score, once a game of the match is won.
+        if controller.score != [0, 0] {
+            let mut x = panel_x;
+            for &(player, color) in &[
+                (Player::First, settings.player_1_color),
+                (Player::Second, settings.player_2_color),
+            ] {
+                text::Text::new_color(color, 26)
+                    .draw(
+                        &controller.wins(player).to_string(),
+                        glyphs,
+                        &c.draw_state,
+                        c.transform.trans(x, 40.),
+                        g,
+                    )
+                    .ok();
+                x += 40.;
+            }
+        }
+        let analysis = &controller.analysis;
+        let mut line_y = settings.position[1] + 20.;
+        if let (true, Some(accounting)) = (controller.show_graphs, &controller.accounting) {
+            // Cumulative luck and skill, one line per player.
+            for &(title, luck) in &[("Luck", true), ("Skill", false)] {
+                let value = |t: &(f64, f64)| if luck { t.0 } else { t.1 };
+                text::Text::new_color(settings.board_edge_color, 22)
+                    .draw(
+                        title,
+                        glyphs,
+                        &c.draw_state,
+                        c.transform.trans(panel_x, line_y),
+                        g,
+                    )
+                    .ok();
+                let chart = [panel_x, line_y + 10., 180., 120.];
+                Rectangle::new_border(settings.board_edge_color, 1.).draw(
+                    chart,
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+                let series = [
+                    (accounting.cumulative(Player::First), settings.player_1_color),
+                    (accounting.cumulative(Player::Second), settings.player_2_color),
+                ];
+                let longest = series.iter().map(|s| s.0.len()).max().unwrap_or(0).max(2);
+                let scale = series
+                    .iter()
+                    .flat_map(|s| s.0.iter().map(|t| value(t).abs()))
+                    .fold(1.0, f64::max);
+                let zero = chart[1] + chart[3] / 2.;
+                Line::new(settings.cell_edge_color, 0.5).draw(
+                    [chart[0], zero, chart[0] + chart[2], zero],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+                for (points, color) in &series {
+                    let point = |i: usize, t: &(f64, f64)| {
+                        [
+                            chart[0] + chart[2] * i as f64 / (longest - 1) as f64,
+                            zero - value(t) / scale * chart[3] / 2.,
+                        ]
+                    };
+                    for (i, pair) in points.windows(2).enumerate() {
+                        let (a, b) = (point(i, &pair[0]), point(i + 1, &pair[1]));
+                        Line::new(*color, 1.).draw(
+                            [a[0], a[1], b[0], b[1]],
+                            &c.draw_state,
+                            c.transform,
+                            g,
+                        );
+                    }
+                }
+                line_y += 160.;
+            }
+        } else if let Some(ref report) = controller.report {
+            text::Text::new_color(settings.board_edge_color, 22)
+                .draw(
+                    "Accuracy",
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(panel_x, line_y),
+                    g,
+                )
+                .ok();
+            for &(player, name, color) in &[
+                (Player::First, "First", settings.player_1_color),
+                (Player::Second, "Second", settings.player_2_color),
+            ] {
+                line_y += 26.;
+                text::Text::new_color(color, 18)
+                    .draw(
+                        &format!("{}: {:.0}%", name, report.accuracy(player)),
+                        glyphs,
+                        &c.draw_state,
+                        c.transform.trans(panel_x + 4., line_y),
+                        g,
+                    )
+                    .ok();
+            }
+            line_y += 40.;
+            text::Text::new_color(settings.board_edge_color, 22)
+                .draw(
+                    "Blunders",
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(panel_x, line_y),
+                    g,
+                )
+                .ok();
+            for blunder in report.blunders.iter().take(8) {
+                line_y += 22.;
+                let color = match blunder.player {
+                    Player::First => settings.player_1_color,
+                    Player::Second => settings.player_2_color,
+                };
+                let played = blunder
+                    .played
+                    .map_or("pass".to_string(), |cell| cell.to_string());
+                text::Text::new_color(color, 16)
+                    .draw(
+                        &format!(
+                            "#{}: {} not {}, -{:.0}",
+                            blunder.turn + 1,
+                            played,
+                            blunder.best,
+                            blunder.loss
+                        ),
+                        glyphs,
+                        &c.draw_state,
+                        c.transform.trans(panel_x, line_y),
+                        g,
+                    )
+                    .ok();
+            }
+            text::Text::new_color(settings.board_edge_color, 14)
+                .draw(
+                    "Click here for luck and skill",
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(panel_x, settings.position[1] + 360.),
+                    g,
+                )
+                .ok();
+        } else {
+            text::Text::new_color(settings.board_edge_color, 22)
+                .draw(
+                    "Win chance",
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(panel_x, line_y),
+                    g,
+                )
+                .ok();
+            for &(player, name, color) in &[
+                (Player::First, "First", settings.player_1_color),
+                (Player::Second, "Second", settings.player_2_color),
+            ] {
+                line_y += 26.;
+                let p = analysis.win_probability(player);
+                // Bar behind the text, scaled to the probability.
+                Rectangle::new(settings.background_color).draw(
+                    [panel_x, line_y - 18., 180. * p, 22.],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+                text::Text::new_color(color, 18)
                     .draw(
-                        &format!("{}", x),
+                        &format!("{}: {:.0}%", name, p * 100.),
                         glyphs,
                         &c.draw_state,
-                        c.transform.trans(10. + 60. * 3.5, 60. * 6.6),
+                        c.transform.trans(panel_x + 4., line_y),
                         g,
                     )
                     .ok();
             }
-            None => {}
+            if !analysis.moves.is_empty() {
+                line_y += 40.;
+                text::Text::new_color(settings.board_edge_color, 22)
+                    .draw(
+                        "Moves",
+                        glyphs,
+                        &c.draw_state,
+                        c.transform.trans(panel_x, line_y),
+                        g,
+                    )
+                    .ok();
+                for evaluation in &analysis.moves {
+                    line_y += 24.;
+                    text::Text::new_color(settings.board_edge_color, 18)
+                        .draw(
+                            &format!(
+                                "{} to {}: {:.0}%",
+                                evaluation.cell,
+                                evaluation.cell + controller.gameboard.dice_roll.unwrap_or(0) as usize,
+                                evaluation.win_probability * 100.
+                            ),
+                            glyphs,
+                            &c.draw_state,
+                            c.transform.trans(panel_x, line_y),
+                            g,
+                        )
+                        .ok();
+                }
+            }
         }
 
         // Draw Victory screen
-        if controller.gameboard.is_finished() {
-            let color = if controller.gameboard.player_1[15] == 7 {
+        if let Some(winner) = controller.winner() {
+            let color = if winner == Player::First {
                 self.settings.player_1_color
             } else {
                 self.settings.player_2_color
             };
-            let text = if controller.gameboard.player_1[15] == 7 {
+            let text = if winner == Player::First {
                 "First player"
             } else {
                 "Second player"
             };
-            let trans = if controller.gameboard.player_1[15] == 7 {
+            let trans = if winner == Player::First {
                 (20. + 60. * 2., 60. * 4. - 45.)
             } else {
                 (48. + 60., 60. * 4. - 45.)
@@ -418,15 +840,61 @@ impl GameboardView {
                     g,
                 )
                 .ok();
+            let (wins, wins_x) = if controller.gameboard.is_finished() {
+                ("wins!", 20. + 60. * 3.)
+            } else {
+                ("wins on time!", 20. + 60. * 1.6)
+            };
             text::Text::new_color(settings.board_edge_color, 45)
                 .draw(
-                    "wins!",
+                    wins,
                     glyphs,
                     &c.draw_state,
-                    c.transform.trans(20. + 60. * 3., 60. * 4. + 20.),
+                    c.transform.trans(wins_x, 60. * 4. + 20.),
                     g,
                 )
                 .ok();
+
+            // Buttons to play again, between "Roll" and "Pass". A replay
+            // can only go back to the menu.
+            let swap = if controller.swap_colours {
+                "Swap colours: on"
+            } else {
+                "Swap colours: off"
+            };
+            let labels = [("Rematch", true), (swap, true), ("Back to menu", false)];
+            for (i, &(label, playing_only)) in labels.iter().enumerate() {
+                if playing_only && controller.replay.is_some() {
+                    continue;
+                }
+                let rect = [
+                    settings.position[0] + END_BUTTONS_LEFT,
+                    settings.position[1] + END_BUTTONS_TOP + i as f64 * END_BUTTON_HEIGHT,
+                    END_BUTTONS_WIDTH,
+                    END_BUTTON_HEIGHT - 4.,
+                ];
+                Rectangle::new(settings.background_color).draw(
+                    rect,
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+                Rectangle::new_border(settings.board_edge_color, settings.board_edge_radius).draw(
+                    rect,
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+                text::Text::new_color(settings.board_edge_color, 20)
+                    .draw(
+                        label,
+                        glyphs,
+                        &c.draw_state,
+                        c.transform.trans(rect[0] + 12., rect[1] + 25.),
+                        g,
+                    )
+                    .ok();
+            }
         }
     }
 }
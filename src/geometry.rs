@@ -0,0 +1,171 @@
+//! Board geometry: where each path cell lies on the grid.
+//!
+//! Grid cells are `(row, column)` pairs. Row 0 is the first player's side,
+//! row 2 the second player's and row 1 the shared middle lane.
+
+use crate::gameboard::Player;
+
+/// Grid cell as `(row, column)`.
+pub type Cell = (i8, i8);
+
+/// Track of the standard game for the first player. Path cell 0 is the
+/// reserve and the last one the finish; both sit in the gap in the board.
+const STANDARD_TRACK_1: [Cell; 16] = [
+    (0, 4),
+    (0, 3),
+    (0, 2),
+    (0, 1),
+    (0, 0),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+    (1, 7),
+    (0, 7),
+    (0, 6),
+    (0, 5),
+];
+
+/// `STANDARD_TRACK_1` mirrored onto the second player's side.
+const STANDARD_TRACK_2: [Cell; 16] = [
+    (2, 4),
+    (2, 3),
+    (2, 2),
+    (2, 1),
+    (2, 0),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+    (1, 7),
+    (2, 7),
+    (2, 6),
+    (2, 5),
+];
+
+/// Masters track for the first player: up the middle lane, across the far
+/// end of the opponent's side and back home.
+const MASTERS_TRACK_1: [Cell; 18] = [
+    (0, 4),
+    (0, 3),
+    (0, 2),
+    (0, 1),
+    (0, 0),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+    (2, 6),
+    (2, 7),
+    (1, 7),
+    (0, 7),
+    (0, 6),
+    (0, 5),
+];
+
+/// `MASTERS_TRACK_1` mirrored onto the second player's side.
+const MASTERS_TRACK_2: [Cell; 18] = [
+    (2, 4),
+    (2, 3),
+    (2, 2),
+    (2, 1),
+    (2, 0),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+    (0, 6),
+    (0, 7),
+    (1, 7),
+    (2, 7),
+    (2, 6),
+    (2, 5),
+];
+
+/// Shape of the board and the route each player's pieces take across it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardGeometry {
+    /// Grid size as `(rows, columns)`.
+    pub size: (i8, i8),
+    tracks: [&'static [Cell]; 2],
+}
+
+impl BoardGeometry {
+    /// The 14-square track of the standard game.
+    pub const STANDARD: BoardGeometry = BoardGeometry {
+        size: (3, 8),
+        tracks: [&STANDARD_TRACK_1, &STANDARD_TRACK_2],
+    };
+
+    /// The 16-square track of James Masters' rules.
+    pub const MASTERS: BoardGeometry = BoardGeometry {
+        size: (3, 8),
+        tracks: [&MASTERS_TRACK_1, &MASTERS_TRACK_2],
+    };
+
+    /// Geometry for another track shape. Both tracks must have the same
+    /// length and stay inside the grid.
+    pub fn new(size: (i8, i8), track_1: &'static [Cell], track_2: &'static [Cell]) -> Self {
+        assert_eq!(track_1.len(), track_2.len());
+        assert!(track_1
+            .iter()
+            .chain(track_2)
+            .all(|&(row, column)| (0..size.0).contains(&row) && (0..size.1).contains(&column)));
+        BoardGeometry {
+            size,
+            tracks: [track_1, track_2],
+        }
+    }
+
+    fn track(&self, player: Player) -> &'static [Cell] {
+        match player {
+            Player::First => self.tracks[0],
+            Player::Second => self.tracks[1],
+        }
+    }
+
+    /// Number of path cells, including the reserve and the finish.
+    pub fn path_len(&self) -> usize {
+        self.tracks[0].len()
+    }
+
+    /// Path cell of finished pieces.
+    pub fn finish(&self) -> usize {
+        self.path_len() - 1
+    }
+
+    /// Grid cell of `player`'s path cell `path`.
+    pub fn grid_cell(&self, player: Player, path: usize) -> Option<Cell> {
+        self.track(player).get(path).cloned()
+    }
+
+    /// `player`'s path cell at grid cell `cell`.
+    pub fn path_cell(&self, player: Player, cell: Cell) -> Option<usize> {
+        self.track(player).iter().position(|&c| c == cell)
+    }
+
+    /// Whether a piece can stand on `cell`, as opposed to the gaps in the board.
+    pub fn is_square(&self, cell: Cell) -> bool {
+        self.tracks
+            .iter()
+            .any(|track| track[1..track.len() - 1].contains(&cell))
+    }
+}
+
+impl Default for BoardGeometry {
+    fn default() -> Self {
+        BoardGeometry::STANDARD
+    }
+}
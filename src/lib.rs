@@ -0,0 +1,53 @@
+//! Rules engine for the Royal game of UR.
+
+// pyo3's macros expand to `::core` paths.
+#[cfg(feature = "python")]
+extern crate core;
+extern crate dirs;
+extern crate flate2;
+#[macro_use]
+extern crate log;
+#[cfg(feature = "python")]
+extern crate pyo3;
+extern crate rand;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+#[cfg(feature = "server")]
+extern crate tiny_http;
+extern crate toml;
+#[cfg(feature = "server")]
+extern crate tungstenite;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+pub use crate::gameboard::{Gameboard, Player};
+
+pub mod analysis;
+pub mod assets;
+pub mod bitboard;
+pub mod bot;
+pub mod cli;
+pub mod clock;
+pub mod dice;
+pub mod env;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod gameboard;
+pub mod geometry;
+pub mod logging;
+pub mod luck;
+#[cfg(feature = "python")]
+mod python;
+pub mod record;
+pub mod report;
+pub mod rules;
+pub mod save;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod settings;
+pub mod tablebase;
+pub mod theme;
+#[cfg(feature = "wasm")]
+pub mod wasm;
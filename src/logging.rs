@@ -0,0 +1,228 @@
+//! Logging for the frontends.
+//!
+//! `Logger` prints the `log` macros' records to stderr at the level given
+//! on the command line or in `UR_LOG`. Game events are also logged through
+//! it, and can be kept as a JSON log per game by `GameLog`.
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::gameboard::{Gameboard, Player};
+
+/// Environment variable holding the log level, e.g. `debug`.
+pub const LOG_ENV: &str = "UR_LOG";
+
+/// Writes every record up to its level to stderr.
+pub struct Logger {
+    level: LevelFilter,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs a `Logger` at `level`, or else at the level in `UR_LOG`, or
+/// else for warnings and errors. An invalid `UR_LOG` is reported and
+/// ignored.
+pub fn init(level: Option<LevelFilter>) -> Result<(), SetLoggerError> {
+    let from_env = env::var(LOG_ENV)
+        .ok()
+        .and_then(|env| match parse_level(&env) {
+            Ok(level) => Some(level),
+            Err(e) => {
+                eprintln!("Ignoring {}: {}", LOG_ENV, e);
+                None
+            }
+        });
+    let level = level.or(from_env).unwrap_or(LevelFilter::Warn);
+    log::set_logger(Box::leak(Box::new(Logger { level })))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// Parses a level such as `info`, or `off`.
+pub fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .parse()
+        .map_err(|_| format!("unknown log level \"{}\"", level))
+}
+
+/// Something that happened in a game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// A game began, or resumed, from `position`.
+    Start {
+        ruleset: String,
+        position: String,
+    },
+    Roll {
+        player: Player,
+        roll: i8,
+    },
+    /// A piece moved from path cell `from` to `to`.
+    Move {
+        player: Player,
+        from: usize,
+        to: usize,
+        capture: bool,
+    },
+    Pass {
+        player: Player,
+    },
+    /// Input that had no effect, e.g. clicking a piece that cannot move.
+    Invalid {
+        player: Player,
+        input: String,
+    },
+    /// `player` ran out of time, and lost if `forfeit`.
+    Timeout {
+        player: Player,
+        forfeit: bool,
+    },
+    End {
+        winner: Player,
+    },
+}
+
+impl GameEvent {
+    pub fn start(gameboard: &Gameboard) -> GameEvent {
+        GameEvent::Start {
+            ruleset: gameboard.ruleset.name.clone(),
+            position: gameboard.to_notation(),
+        }
+    }
+
+    /// The active player moving the piece on `cell` by the roll, described
+    /// before the move is made.
+    pub fn play(gameboard: &Gameboard, cell: usize) -> GameEvent {
+        GameEvent::Move {
+            player: gameboard.active_player,
+            from: cell,
+            to: cell + gameboard.dice_roll.unwrap_or(0) as usize,
+            capture: gameboard.captures(cell),
+        }
+    }
+
+    /// Logs the event: captures, timeouts, starts and ends as info, invalid input as
+    /// a warning and everything else for debugging.
+    pub fn log(&self) {
+        match *self {
+            GameEvent::Start {
+                ref ruleset,
+                ref position,
+            } => info!("Game started: {} rules from {}", ruleset, position),
+            GameEvent::Roll { player, roll } => debug!("{:?} rolled {}", player, roll),
+            GameEvent::Move {
+                player,
+                from,
+                to,
+                capture,
+            } => {
+                let level = if capture { Level::Info } else { Level::Debug };
+                let captures = if capture { ", capturing" } else { "" };
+                log!(
+                    level,
+                    "{:?} moved from {} to {}{}",
+                    player,
+                    from,
+                    to,
+                    captures
+                );
+            }
+            GameEvent::Pass { player } => debug!("{:?} passed", player),
+            GameEvent::Invalid { player, ref input } => {
+                warn!("Invalid input from {:?}: {}", player, input)
+            }
+            GameEvent::Timeout { player, forfeit } => {
+                let outcome = if forfeit { "forfeits" } else { "is moved for" };
+                info!("{:?} ran out of time and {}", player, outcome)
+            }
+            GameEvent::End { winner } => info!("Game over: {:?} won", winner),
+        }
+    }
+}
+
+/// An event and the seconds since its game log was created.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub time: f64,
+    #[serde(flatten)]
+    pub event: GameEvent,
+}
+
+/// A game's events, one JSON `LogEntry` per line.
+pub struct GameLog {
+    file: File,
+    path: PathBuf,
+    created: Instant,
+}
+
+impl GameLog {
+    /// Creates a new log in `dir`, named after the current time.
+    pub fn create<P: AsRef<Path>>(dir: P) -> io::Result<GameLog> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_millis());
+        // Games started within the same millisecond get a suffix.
+        for n in 0.. {
+            let name = match n {
+                0 => format!("game-{}.jsonl", millis),
+                n => format!("game-{}-{}.jsonl", millis, n),
+            };
+            let path = dir.join(name);
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    return Ok(GameLog {
+                        file,
+                        path,
+                        created: Instant::now(),
+                    })
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write(&mut self, event: &GameEvent) -> io::Result<()> {
+        let entry = LogEntry {
+            time: self.created.elapsed().as_secs_f64(),
+            event: event.clone(),
+        };
+        let json = serde_json::to_string(&entry).expect("log entry serializes");
+        writeln!(self.file, "{}", json)
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Vec<LogEntry>> {
+        BufReader::new(File::open(path)?)
+            .lines()
+            .map(|line| {
+                serde_json::from_str(&line?)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,87 @@
+//! Splits a game's outcome into luck and skill.
+//!
+//! For every turn, luck is how far the roll moved the mover's win probability
+//! from its expectation before rolling, and skill is the win probability the
+//! chosen move gave up against the best one. Both are in percentage points.
+
+use crate::analysis;
+use crate::gameboard::Player;
+use crate::record::GameRecord;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TurnAccount {
+    /// Index into the record's turns.
+    pub turn: usize,
+    pub player: Player,
+    /// Positive when the roll was better than expected.
+    pub luck: f64,
+    /// Zero for the best move, negative otherwise.
+    pub skill: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Accounting {
+    pub turns: Vec<TurnAccount>,
+}
+
+impl Accounting {
+    /// Accounts for every rolled turn of `record`, searching `depth` rolls ahead.
+    pub fn new(record: &GameRecord, depth: u32) -> Accounting {
+        let mut turns = Vec::new();
+        for (i, turn) in record.turns.iter().enumerate() {
+            let rolled = match record.position(i) {
+                Some(g) => g,
+                None => continue,
+            };
+            if rolled.dice_roll.is_none() {
+                continue;
+            }
+            let mut unrolled = rolled.clone();
+            unrolled.dice_roll = None;
+            let expected = analysis::evaluate(&unrolled, depth);
+            let best = analysis::evaluate(&rolled, depth);
+
+            let moves = analysis::analyse(&rolled, depth).moves;
+            let played = match turn.cell {
+                Some(cell) => moves
+                    .iter()
+                    .find(|m| m.cell == cell)
+                    .map_or(best, |m| m.win_probability),
+                None if moves.is_empty() => best,
+                None => {
+                    let mut passed = rolled.clone();
+                    passed.pass_turn();
+                    1.0 - analysis::evaluate(&passed, depth.saturating_sub(1))
+                }
+            };
+            turns.push(TurnAccount {
+                turn: i,
+                player: rolled.active_player,
+                luck: (best - expected) * 100.0,
+                skill: (played - best).min(0.0) * 100.0,
+            });
+        }
+        Accounting { turns }
+    }
+
+    /// Running `(luck, skill)` totals after each of `player`'s turns.
+    pub fn cumulative(&self, player: Player) -> Vec<(f64, f64)> {
+        self.turns
+            .iter()
+            .filter(|t| t.player == player)
+            .scan((0.0, 0.0), |total, t| {
+                total.0 += t.luck;
+                total.1 += t.skill;
+                Some(*total)
+            })
+            .collect()
+    }
+
+    pub fn luck(&self, player: Player) -> f64 {
+        self.cumulative(player).last().map_or(0.0, |t| t.0)
+    }
+
+    pub fn skill(&self, player: Player) -> f64 {
+        self.cumulative(player).last().map_or(0.0, |t| t.1)
+    }
+}
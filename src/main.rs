@@ -1,62 +1,347 @@
 #![windows_subsystem = "windows"]
 
 //! A Royal game of UR.
+//!
+//! Run with `--help` for the command-line options.
 
 extern crate glutin_window;
 extern crate graphics;
+#[macro_use]
+extern crate log;
 extern crate opengl_graphics;
 extern crate piston;
 extern crate rand;
+extern crate royal_game_of_ur;
+
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process;
 
 use glutin_window::GlutinWindow;
 use opengl_graphics::{Filter, GlGraphics, GlyphCache, OpenGL, TextureSettings};
-use piston::event_loop::{EventLoop, EventSettings, Events};
-use piston::input::RenderEvent;
-use piston::window::WindowSettings;
+use piston::event_loop::{EventSettings, Events};
+use piston::input::{RenderEvent, UpdateEvent};
+use piston::window::{AdvancedWindow, WindowSettings};
+use rand::rngs::StdRng;
+use rand::{FromEntropy, SeedableRng};
+
+use royal_game_of_ur::assets;
+use royal_game_of_ur::bot;
+use royal_game_of_ur::cli::{self, Options};
+use royal_game_of_ur::clock::Clock;
+use royal_game_of_ur::logging::{self, GameLog};
+use royal_game_of_ur::record::GameRecord;
+use royal_game_of_ur::save::SaveGame;
+use royal_game_of_ur::settings::{self, Settings};
+use royal_game_of_ur::theme::Theme;
+
+pub use royal_game_of_ur::{gameboard, Gameboard};
 
-pub use crate::gameboard::Gameboard;
 pub use crate::gameboard_controller::GameboardController;
 pub use crate::gameboard_view::{GameboardView, GameboardViewSettings};
+pub use crate::menu_controller::{GameSetup, MenuAction, MenuController};
+pub use crate::menu_view::MenuView;
+pub use crate::settings_controller::{SettingsAction, SettingsController};
+pub use crate::sprites::Sprites;
 
-mod gameboard;
+mod form;
 mod gameboard_controller;
 mod gameboard_view;
+mod menu_controller;
+mod menu_view;
+mod settings_controller;
+mod sprites;
+
+/// Asset with the font used unless `--font` names another.
+const FONT_FILE: &str = "FiraSans-Regular.ttf";
+/// The same font, for when the assets cannot be found.
+const BUNDLED_FONT: &[u8] = include_bytes!("../assets/FiraSans-Regular.ttf");
+
+/// What the window is showing.
+enum Screen {
+    Menu(MenuController),
+    Game(Box<GameboardController>),
+    /// The settings screen, then the menu setup to return to.
+    Settings(SettingsController, GameSetup),
+}
+
+impl Screen {
+    fn name(&self) -> &'static str {
+        match *self {
+            Screen::Menu(_) => "menu",
+            Screen::Game(ref controller) if controller.replay.is_some() => "replay",
+            Screen::Game(_) => "game",
+            Screen::Settings(..) => "settings",
+        }
+    }
+}
 
 fn main() {
+    attach_console();
+    let options = Options::parse(env::args().skip(1)).unwrap_or_else(|e| usage(&e));
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    logging::init(options.log).expect("Could not set up logging");
+    let mut settings = options
+        .apply(&Settings::load())
+        .unwrap_or_else(|e| usage(&e));
+    let mut setup = default_setup(&settings);
+    setup.seed = options.seed;
+    if options.headless {
+        if let Err(e) = headless(&setup, options.load.as_deref(), options.game_log.as_deref()) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    let themes = Theme::load();
+    let theme_names: Vec<String> = themes.iter().map(|t| t.name.clone()).collect();
+    if options.theme.is_some() && !theme_names.contains(&settings.theme) {
+        usage(&format!("unknown theme \"{}\"", settings.theme));
+    }
+
     let opengl = OpenGL::V3_2;
-    let settings = WindowSettings::new("Royal Game of Ur", [500, 432])
+    let window_settings = WindowSettings::new("Royal Game of Ur", settings.window)
         .graphics_api(opengl)
         .exit_on_esc(true);
-    let mut window: GlutinWindow = settings.build().expect("Could not create window");
+    let mut window: GlutinWindow = window_settings.build().expect("Could not create window");
 
-    let mut events = Events::new(EventSettings::new().lazy(true));
+    // Not lazy: bots move on update events.
+    let mut events = Events::new(EventSettings::new());
     let mut gl = GlGraphics::new(opengl);
+    let sprites = match assets::find(sprites::SPRITES_DIR) {
+        Some(dir) => Sprites::load(dir),
+        None => Sprites::default(),
+    };
 
-    let gameboard = Gameboard::new();
-
-    let mut gameboard_controller = GameboardController::new(gameboard);
-    let gameboard_view_settings = GameboardViewSettings::new();
-    let gameboard_view = GameboardView::new(gameboard_view_settings);
+    let mut screen = match options.load {
+        Some(ref path) => match load_game(path, &setup) {
+            Ok(controller) => play(controller, &settings, options.game_log.as_deref()),
+            Err(e) => usage(&e),
+        },
+        None => Screen::Menu(MenuController::new(setup)),
+    };
+    let theme = find_theme(&themes, &settings.theme);
+    let mut gameboard_view = GameboardView::new(GameboardViewSettings::with_theme(theme));
+    let mut menu_view = MenuView::new(GameboardViewSettings::with_theme(theme));
 
     let texture_settings = TextureSettings::new().filter(Filter::Nearest);
-    let ref mut glyphs = GlyphCache::new("assets/FiraSans-Regular.ttf", (), texture_settings)
-        .expect("Could not load font");
+    let glyphs = &mut match options.font.clone().or_else(|| assets::find(FONT_FILE)) {
+        Some(path) => GlyphCache::new(path, (), texture_settings).expect("Could not load font"),
+        None => GlyphCache::from_bytes(BUNDLED_FONT, (), texture_settings)
+            .expect("Could not load the bundled font"),
+    };
 
     while let Some(e) = events.next(&mut window) {
-        gameboard_controller.event(
-            gameboard_view.settings.position,
-            [
-                gameboard_view.settings.size_x,
-                gameboard_view.settings.size_y,
-            ],
-            &e,
-        );
+        let mut next = None;
+        match screen {
+            Screen::Menu(ref mut menu) => {
+                menu.event(&e);
+                match menu.action.take() {
+                    Some(MenuAction::Settings) => {
+                        next = Some(Screen::Settings(
+                            SettingsController::new(settings.clone(), theme_names.clone()),
+                            menu.setup.clone(),
+                        ))
+                    }
+                    Some(action) => match start(action, &menu.setup) {
+                        Ok(controller) => {
+                            next = Some(play(controller, &settings, options.game_log.as_deref()))
+                        }
+                        Err(message) => menu.message = Some(message),
+                    },
+                    None => {}
+                }
+            }
+            Screen::Game(ref mut controller) => {
+                controller.event(
+                    gameboard_view.settings.position,
+                    [
+                        gameboard_view.settings.size_x,
+                        gameboard_view.settings.size_y,
+                    ],
+                    &e,
+                );
+                if let Some(args) = e.update_args() {
+                    controller.update(args.dt);
+                }
+                if controller.back_to_menu {
+                    next = Some(Screen::Menu(MenuController::new(controller.setup.clone())));
+                }
+            }
+            Screen::Settings(ref mut form, ref setup) => {
+                form.event(&e);
+                match form.action.take() {
+                    Some(SettingsAction::Save) => {
+                        settings = form.settings.clone();
+                        let theme = find_theme(&themes, &settings.theme);
+                        gameboard_view.settings = GameboardViewSettings::with_theme(theme);
+                        menu_view.settings = GameboardViewSettings::with_theme(theme);
+                        window.set_size(settings.window);
+                        let mut menu = MenuController::new(default_setup(&settings));
+                        if let Err(e) = settings.save() {
+                            menu.message = Some(format!("Could not save settings: {}", e));
+                        }
+                        next = Some(Screen::Menu(menu));
+                    }
+                    Some(SettingsAction::Back) => {
+                        next = Some(Screen::Menu(MenuController::new(setup.clone())))
+                    }
+                    None => {}
+                }
+            }
+        }
+        if let Some(next) = next {
+            info!("Leaving the {} for the {}", screen.name(), next.name());
+            screen = next;
+        }
         if let Some(args) = e.render_args() {
             gl.draw(args.viewport(), |c, g| {
                 use graphics::clear;
-                clear([0.9; 4], g);
-                gameboard_view.draw(&gameboard_controller, glyphs, &c, g);
+                clear(gameboard_view.settings.window_color, g);
+                match screen {
+                    Screen::Menu(ref menu) => menu_view.draw(menu, glyphs, &c, g),
+                    Screen::Game(ref controller) => {
+                        gameboard_view.draw(controller, &sprites, glyphs, &c, g)
+                    }
+                    Screen::Settings(ref form, _) => menu_view.draw_settings(form, glyphs, &c, g),
+                }
             });
         }
     }
 }
+
+/// Menu setup with the default players and rules from `settings`.
+fn default_setup(settings: &Settings) -> GameSetup {
+    GameSetup {
+        players: settings.players(),
+        ruleset: settings.ruleset().unwrap_or_default(),
+        ..GameSetup::default()
+    }
+}
+
+/// Theme called `name`, or the first one if there is none.
+fn find_theme<'a>(themes: &'a [Theme], name: &str) -> &'a Theme {
+    themes.iter().find(|t| t.name == name).unwrap_or(&themes[0])
+}
+
+/// Builds the game for a menu choice, or explains why it cannot start.
+fn start(action: MenuAction, setup: &GameSetup) -> Result<GameboardController, String> {
+    match action {
+        MenuAction::NewGame => Ok(GameboardController::from_setup(setup)),
+        MenuAction::Continue => load_game(
+            &settings::data_path(gameboard_controller::SAVE_FILE),
+            setup,
+        ),
+        MenuAction::Replay => {
+            let record = GameRecord::read(settings::data_path(gameboard_controller::LAST_GAME_FILE))
+                .map_err(|e| match e.kind() {
+                    io::ErrorKind::InvalidData => "The last game is damaged".to_string(),
+                    _ => format!("No finished game to replay: {}", e),
+                })?;
+            GameboardController::replay(record)
+                .ok_or_else(|| "The last game has no turns to replay".to_string())
+        }
+        MenuAction::Settings => unreachable!("the settings screen is not a game"),
+    }
+}
+
+/// Shows `controller`'s game with the bot delay from `settings`, and logs
+/// it to a file in `game_log` if given.
+fn play(
+    mut controller: GameboardController,
+    settings: &Settings,
+    game_log: Option<&Path>,
+) -> Screen {
+    controller.bot_delay = settings.bot_delay;
+    controller.game_log_dir = game_log.map(Path::to_path_buf);
+    controller.start_log();
+    Screen::Game(Box::new(controller))
+}
+
+/// Continues the game saved at `path`, clocks included, with the players
+/// of `setup`.
+fn load_game(path: &Path, setup: &GameSetup) -> Result<GameboardController, String> {
+    let (gameboard, clock) = read_save(path)?;
+    let mut controller = GameboardController::new(gameboard);
+    controller.setup.players = setup.players;
+    controller.setup.time_control = clock.as_ref().map(|clock| clock.control);
+    controller.clock = clock;
+    Ok(controller)
+}
+
+fn read_save(path: &Path) -> Result<(Gameboard, Option<Clock>), String> {
+    let save = SaveGame::read(path).map_err(|e| match e.kind() {
+        io::ErrorKind::InvalidData => "The saved game is damaged".to_string(),
+        _ => format!("No saved game: {}", e),
+    })?;
+    let gameboard = save
+        .to_gameboard()
+        .ok_or_else(|| "The saved game is damaged".to_string())?;
+    let lost_on_time = save.clock.as_ref().and_then(Clock::winner);
+    if gameboard.is_finished() || lost_on_time.is_some() {
+        return Err("The saved game is already over".to_string());
+    }
+    Ok((gameboard, save.clock))
+}
+
+/// Plays the bots of `setup` against each other from a new game, or from
+/// the game saved at `load`, and prints the record. Also logs the game to
+/// a file in `game_log` if given.
+fn headless(setup: &GameSetup, load: Option<&Path>, game_log: Option<&Path>) -> Result<(), String> {
+    let bots = match setup.players {
+        [Some(first), Some(second)] => [first, second],
+        _ => return Err("--headless needs a bot for each player, e.g. --p1 bot:greedy".to_string()),
+    };
+    let gameboard = match load {
+        Some(path) => read_save(path)?.0,
+        None => {
+            let mut gameboard = Gameboard::with_ruleset(setup.ruleset.clone());
+            gameboard.active_player = setup.first;
+            gameboard
+        }
+    };
+    let mut rng = match setup.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let tablebase = gameboard_controller::load_tablebase();
+    let mut log = match game_log {
+        Some(dir) => Some(
+            GameLog::create(dir)
+                .map_err(|e| format!("Could not create a game log in {}: {}", dir.display(), e))?,
+        ),
+        None => None,
+    };
+    let record = bot::play_out(gameboard, bots, tablebase.as_ref(), &mut rng, log.as_mut());
+    println!("{}", record.to_json());
+    Ok(())
+}
+
+fn usage(error: &str) -> ! {
+    eprintln!("{}\n\n{}", error, cli::USAGE);
+    process::exit(2);
+}
+
+/// Shares the console the game was started from, if any, so that
+/// `--help`, usage errors and `--headless` games still print on Windows,
+/// where the window has no console of its own.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails when started from Explorer, which has no console to share.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
@@ -0,0 +1,267 @@
+//! Main menu controller.
+
+use piston::input::{GenericEvent, Key};
+
+use royal_game_of_ur::bot::Bot;
+use royal_game_of_ur::clock::{TimeControl, Timeout};
+use royal_game_of_ur::rules::{ExtraTurns, Ruleset};
+
+use crate::form::{self, Form, FormEvent};
+use crate::gameboard::Player;
+
+/// Piece counts offered in the menu.
+pub const PIECE_COUNTS: [u8; 4] = [3, 5, 7, 10];
+
+/// Everything chosen on the menu before a game starts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameSetup {
+    /// `None` for a human player, first player then second.
+    pub players: [Option<Bot>; 2],
+    pub ruleset: Ruleset,
+    pub first: Player,
+    /// `None` seeds the dice from the system.
+    pub seed: Option<u64>,
+    /// `None` for an untimed game.
+    pub time_control: Option<TimeControl>,
+}
+
+impl Default for GameSetup {
+    fn default() -> Self {
+        GameSetup {
+            players: [None, None],
+            ruleset: Ruleset::default(),
+            first: Player::First,
+            seed: None,
+            time_control: None,
+        }
+    }
+}
+
+/// A setting row of the menu.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuRow {
+    FirstPlayer,
+    SecondPlayer,
+    Ruleset,
+    Pieces,
+    ExtraTurns,
+    Starts,
+    Seed,
+    TimeControl,
+    Timeout,
+}
+
+impl MenuRow {
+    pub const ALL: [MenuRow; 9] = [
+        MenuRow::FirstPlayer,
+        MenuRow::SecondPlayer,
+        MenuRow::Ruleset,
+        MenuRow::Pieces,
+        MenuRow::ExtraTurns,
+        MenuRow::Starts,
+        MenuRow::Seed,
+        MenuRow::TimeControl,
+        MenuRow::Timeout,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuRow::FirstPlayer => "First player",
+            MenuRow::SecondPlayer => "Second player",
+            MenuRow::Ruleset => "Rules",
+            MenuRow::Pieces => "Pieces",
+            MenuRow::ExtraTurns => "Extra turns",
+            MenuRow::Starts => "Starts",
+            MenuRow::Seed => "Seed",
+            MenuRow::TimeControl => "Clock",
+            MenuRow::Timeout => "Out of time",
+        }
+    }
+}
+
+/// What the player picked on the menu.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuAction {
+    NewGame,
+    Continue,
+    Replay,
+    Settings,
+}
+
+impl MenuAction {
+    pub const ALL: [MenuAction; 4] = [
+        MenuAction::NewGame,
+        MenuAction::Continue,
+        MenuAction::Replay,
+        MenuAction::Settings,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuAction::NewGame => "Start",
+            MenuAction::Continue => "Continue",
+            MenuAction::Replay => "Replay",
+            MenuAction::Settings => "Settings",
+        }
+    }
+}
+
+/// Human first, then every bot.
+pub fn player_choices() -> Vec<Option<Bot>> {
+    Some(None)
+        .into_iter()
+        .chain(Bot::ALL.iter().map(|&b| Some(b)))
+        .collect()
+}
+
+/// How a player choice reads on screen.
+pub fn player_label(player: Option<Bot>) -> String {
+    player.map_or("Human".to_string(), |b| format!("Bot: {}", b.name()))
+}
+
+pub struct MenuController {
+    pub setup: GameSetup,
+    pub form: Form,
+    /// Set when a button is clicked, for the caller to act on.
+    pub action: Option<MenuAction>,
+    /// Shown under the buttons, e.g. when there is no saved game.
+    pub message: Option<String>,
+}
+
+impl MenuController {
+    pub fn new(setup: GameSetup) -> MenuController {
+        MenuController {
+            setup,
+            form: Form::new(),
+            action: None,
+            message: None,
+        }
+    }
+
+    /// Text shown for `row`'s current value.
+    pub fn value(&self, row: MenuRow) -> String {
+        match row {
+            MenuRow::FirstPlayer => player_label(self.setup.players[0]),
+            MenuRow::SecondPlayer => player_label(self.setup.players[1]),
+            MenuRow::Ruleset => self.setup.ruleset.name.clone(),
+            MenuRow::Pieces => self.setup.ruleset.pieces.to_string(),
+            MenuRow::ExtraTurns => self.setup.ruleset.extra_turns.label(),
+            MenuRow::Starts => match self.setup.first {
+                Player::First => "First player".to_string(),
+                Player::Second => "Second player".to_string(),
+            },
+            MenuRow::Seed => self
+                .setup
+                .seed
+                .map_or("Random".to_string(), |seed| seed.to_string()),
+            MenuRow::TimeControl => self
+                .setup
+                .time_control
+                .map_or("Untimed".to_string(), |control| control.label()),
+            MenuRow::Timeout => match self.setup.time_control {
+                Some(control) => control.timeout.label().to_string(),
+                None => "-".to_string(),
+            },
+        }
+    }
+
+    /// Steps `row` to its next value, or its previous one if `!forward`.
+    pub fn cycle(&mut self, row: MenuRow, forward: bool) {
+        match row {
+            MenuRow::FirstPlayer | MenuRow::SecondPlayer => {
+                let side = if row == MenuRow::FirstPlayer { 0 } else { 1 };
+                let choices = player_choices();
+                let i = form::step_choice(&choices, &self.setup.players[side], 0, forward);
+                self.setup.players[side] = choices[i];
+            }
+            MenuRow::Ruleset => {
+                let mut presets = Ruleset::presets();
+                let names: Vec<String> = presets.iter().map(|r| r.name.clone()).collect();
+                let i = form::step_choice(&names, &self.setup.ruleset.name, 0, forward);
+                self.setup.ruleset = Ruleset {
+                    pieces: self.setup.ruleset.pieces,
+                    extra_turns: self.setup.ruleset.extra_turns,
+                    ..presets.remove(i)
+                };
+            }
+            MenuRow::Pieces => {
+                let i = form::step_choice(&PIECE_COUNTS, &self.setup.ruleset.pieces, 2, forward);
+                self.setup.ruleset.pieces = PIECE_COUNTS[i];
+            }
+            MenuRow::ExtraTurns => {
+                let choices = ExtraTurns::presets();
+                let current = &self.setup.ruleset.extra_turns;
+                let i = form::step_choice(&choices, current, 0, forward);
+                self.setup.ruleset.extra_turns = choices[i];
+            }
+            MenuRow::Starts => self.setup.first = self.setup.first.other(),
+            MenuRow::Seed => {
+                self.setup.seed = match (self.setup.seed, forward) {
+                    (None, true) => Some(1),
+                    (None, false) => None,
+                    (Some(seed), true) => Some(seed + 1),
+                    (Some(seed), false) => seed.checked_sub(1).filter(|&s| s > 0),
+                }
+            }
+            MenuRow::TimeControl => {
+                // Untimed first, then the presets, keeping the timeout.
+                let timeout = self
+                    .setup
+                    .time_control
+                    .map_or_else(Timeout::default, |c| c.timeout);
+                let choices: Vec<Option<TimeControl>> = Some(None)
+                    .into_iter()
+                    .chain(
+                        TimeControl::presets()
+                            .into_iter()
+                            .map(|control| Some(TimeControl { timeout, ..control })),
+                    )
+                    .collect();
+                let i = form::step_choice(&choices, &self.setup.time_control, 0, forward);
+                self.setup.time_control = choices[i];
+            }
+            MenuRow::Timeout => {
+                if let Some(ref mut control) = self.setup.time_control {
+                    let i = form::step_choice(&Timeout::ALL, &control.timeout, 0, forward);
+                    control.timeout = Timeout::ALL[i];
+                }
+            }
+        }
+    }
+
+    pub fn event<E: GenericEvent>(&mut self, e: &E) {
+        let selected = MenuRow::ALL[self.form.selected];
+        match self
+            .form
+            .event(e, MenuRow::ALL.len(), MenuAction::ALL.len())
+        {
+            Some(FormEvent::Step(row, forward)) => self.cycle(MenuRow::ALL[row], forward),
+            Some(FormEvent::Button(button)) => self.action = Some(MenuAction::ALL[button]),
+            // Enter starts the game.
+            Some(FormEvent::Key(Key::Return)) => self.action = Some(MenuAction::NewGame),
+            // Typed digits edit the seed.
+            Some(FormEvent::Key(key)) if selected == MenuRow::Seed => {
+                let digit = match key {
+                    Key::D0 => Some(0),
+                    Key::D1 => Some(1),
+                    Key::D2 => Some(2),
+                    Key::D3 => Some(3),
+                    Key::D4 => Some(4),
+                    Key::D5 => Some(5),
+                    Key::D6 => Some(6),
+                    Key::D7 => Some(7),
+                    Key::D8 => Some(8),
+                    Key::D9 => Some(9),
+                    _ => None,
+                };
+                if let Some(digit) = digit {
+                    let seed = self.setup.seed.unwrap_or(0);
+                    self.setup.seed = seed.checked_mul(10).and_then(|s| s.checked_add(digit));
+                } else if key == Key::Backspace {
+                    self.setup.seed = self.setup.seed.map(|s| s / 10).filter(|&s| s > 0);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Main menu and settings screen view.
+
+use graphics::character::CharacterCache;
+use graphics::*;
+use graphics::{Context, Graphics};
+
+use crate::form::{
+    BUTTONS_LEFT, BUTTONS_TOP, BUTTON_GAP, BUTTON_HEIGHT, BUTTON_WIDTH, ROWS_TOP, ROW_HEIGHT,
+    VALUE_LEFT, VALUE_WIDTH,
+};
+use crate::menu_controller::{MenuAction, MenuController, MenuRow};
+use crate::settings_controller::{SettingsAction, SettingsController, SettingsRow};
+use crate::GameboardViewSettings;
+
+/// Draws the menu screens in the gameboard's colours.
+pub struct MenuView {
+    pub settings: GameboardViewSettings,
+}
+
+impl MenuView {
+    pub fn new(settings: GameboardViewSettings) -> MenuView {
+        MenuView { settings }
+    }
+
+    pub fn draw<G: Graphics, C>(
+        &self,
+        controller: &MenuController,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        let rows: Vec<(&str, String)> = MenuRow::ALL
+            .iter()
+            .map(|&row| (row.label(), controller.value(row)))
+            .collect();
+        let buttons: Vec<&str> = MenuAction::ALL.iter().map(|a| a.label()).collect();
+        self.draw_form(
+            "Royal Game of Ur",
+            &rows,
+            controller.form.selected,
+            &buttons,
+            controller.message.as_ref(),
+            glyphs,
+            c,
+            g,
+        );
+    }
+
+    pub fn draw_settings<G: Graphics, C>(
+        &self,
+        controller: &SettingsController,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        let rows: Vec<(&str, String)> = SettingsRow::ALL
+            .iter()
+            .map(|&row| (row.label(), controller.value(row)))
+            .collect();
+        let buttons: Vec<&str> = SettingsAction::ALL.iter().map(|a| a.label()).collect();
+        self.draw_form(
+            "Settings",
+            &rows,
+            controller.form.selected,
+            &buttons,
+            None,
+            glyphs,
+            c,
+            g,
+        );
+    }
+
+    /// Draws `rows` of labels and values above a line of `buttons`, with
+    /// the `selected` row highlighted and an optional `message` below.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_form<G: Graphics, C>(
+        &self,
+        title: &str,
+        rows: &[(&str, String)],
+        selected: usize,
+        buttons: &[&str],
+        message: Option<&String>,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        let settings = &self.settings;
+        let text_color = settings.board_edge_color;
+
+        text::Text::new_color(text_color, 36)
+            .draw(title, glyphs, &c.draw_state, c.transform.trans(40., 48.), g)
+            .ok();
+
+        for (i, (label, value)) in rows.iter().enumerate() {
+            let top = ROWS_TOP + i as f64 * ROW_HEIGHT;
+            text::Text::new_color(text_color, 22)
+                .draw(
+                    label,
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(40., top + ROW_HEIGHT - 6.),
+                    g,
+                )
+                .ok();
+
+            let value_rect = [VALUE_LEFT, top + 4., VALUE_WIDTH, ROW_HEIGHT - 8.];
+            let fill = if i == selected {
+                settings.rosette_color
+            } else {
+                settings.background_color
+            };
+            Rectangle::new(fill).draw(value_rect, &c.draw_state, c.transform, g);
+            Rectangle::new_border(text_color, 1.).draw(value_rect, &c.draw_state, c.transform, g);
+            // Arrows hint that each half of the box steps the value.
+            for &(arrow, x) in &[
+                ("<", VALUE_LEFT + 8.),
+                (">", VALUE_LEFT + VALUE_WIDTH - 20.),
+            ] {
+                text::Text::new_color(text_color, 20)
+                    .draw(
+                        arrow,
+                        glyphs,
+                        &c.draw_state,
+                        c.transform.trans(x, top + ROW_HEIGHT - 6.),
+                        g,
+                    )
+                    .ok();
+            }
+            text::Text::new_color(text_color, 20)
+                .draw(
+                    value,
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(VALUE_LEFT + 40., top + ROW_HEIGHT - 6.),
+                    g,
+                )
+                .ok();
+        }
+
+        for (i, label) in buttons.iter().enumerate() {
+            let left = BUTTONS_LEFT + i as f64 * (BUTTON_WIDTH + BUTTON_GAP);
+            let rect = [left, BUTTONS_TOP, BUTTON_WIDTH, BUTTON_HEIGHT];
+            Rectangle::new(settings.background_color).draw(rect, &c.draw_state, c.transform, g);
+            Rectangle::new_border(text_color, settings.board_edge_radius).draw(
+                rect,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+            text::Text::new_color(text_color, 26)
+                .draw(
+                    label,
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(left + 20., BUTTONS_TOP + 34.),
+                    g,
+                )
+                .ok();
+        }
+
+        if let Some(message) = message {
+            text::Text::new_color(settings.player_1_color, 18)
+                .draw(
+                    message,
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(40., BUTTONS_TOP + BUTTON_HEIGHT + 30.),
+                    g,
+                )
+                .ok();
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Python bindings, built as an extension module with `maturin build`.
+
+// The `#[pymethods]` expansion trips this lint on `PyResult` returns.
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::env::{Environment, ACTIONS, OBSERVATION_SIZE};
+use crate::gameboard::{Gameboard, Player};
+use crate::rules::Ruleset;
+
+fn player_number(player: Player) -> u8 {
+    match player {
+        Player::First => 1,
+        Player::Second => 2,
+    }
+}
+
+#[pyclass(name = "Gameboard")]
+#[derive(Clone)]
+struct PyGameboard {
+    gameboard: Gameboard,
+}
+
+#[pymethods]
+impl PyGameboard {
+    #[new]
+    fn new() -> Self {
+        PyGameboard {
+            gameboard: Gameboard::new(),
+        }
+    }
+
+    #[staticmethod]
+    fn from_notation(notation: &str) -> PyResult<Self> {
+        Gameboard::from_notation(notation)
+            .map(|gameboard| PyGameboard { gameboard })
+            .ok_or_else(|| PyValueError::new_err("Invalid notation"))
+    }
+
+    fn notation(&self) -> String {
+        self.gameboard.to_notation()
+    }
+
+    #[getter]
+    fn player_1(&self) -> Vec<i8> {
+        self.gameboard.player_1.clone()
+    }
+
+    #[getter]
+    fn player_2(&self) -> Vec<i8> {
+        self.gameboard.player_2.clone()
+    }
+
+    /// 1 or 2.
+    #[getter]
+    fn active_player(&self) -> u8 {
+        player_number(self.gameboard.active_player)
+    }
+
+    #[getter]
+    fn dice_roll(&self) -> Option<i8> {
+        self.gameboard.dice_roll
+    }
+
+    fn roll(&mut self, seed: u64) -> i8 {
+        self.gameboard.roll(&mut StdRng::seed_from_u64(seed))
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        self.gameboard.legal_moves()
+    }
+
+    /// Returns `False` if the move is illegal.
+    fn apply_move(&mut self, cell: usize) -> bool {
+        cell < self.gameboard.geometry.path_len() && self.gameboard._move(cell)
+    }
+
+    fn pass_turn(&mut self) {
+        self.gameboard.pass_turn();
+    }
+
+    /// 1 or 2 once the game is won.
+    fn winner(&self) -> Option<u8> {
+        self.gameboard.winner().map(player_number)
+    }
+}
+
+/// Gym-style self-play environment.
+#[pyclass(name = "UrEnv")]
+struct PyEnvironment {
+    env: Environment,
+}
+
+impl PyEnvironment {
+    fn observation(&self) -> (Vec<f32>, Vec<bool>) {
+        let mut observation = vec![0.0; self.env.observation_size()];
+        self.env.observe(&mut observation);
+        (observation, self.env.legal_action_mask())
+    }
+}
+
+#[pymethods]
+impl PyEnvironment {
+    /// Plays the ruleset preset named `ruleset`, Classic by default.
+    #[new]
+    #[pyo3(signature = (seed = 0, ruleset = None))]
+    fn new(seed: u64, ruleset: Option<&str>) -> PyResult<Self> {
+        let ruleset = match ruleset {
+            Some(name) => Ruleset::presets()
+                .into_iter()
+                .find(|r| r.name == name)
+                .ok_or_else(|| PyValueError::new_err("Unknown ruleset"))?,
+            None => Ruleset::classic(),
+        };
+        Ok(PyEnvironment {
+            env: Environment::with_ruleset(ruleset, seed),
+        })
+    }
+
+    #[getter]
+    fn actions(&self) -> usize {
+        self.env.actions()
+    }
+
+    #[getter]
+    fn observation_size(&self) -> usize {
+        self.env.observation_size()
+    }
+
+    /// Returns `(observation, legal_action_mask)`.
+    #[pyo3(signature = (seed = None))]
+    fn reset(&mut self, seed: Option<u64>) -> (Vec<f32>, Vec<bool>) {
+        self.env.reset(seed);
+        self.observation()
+    }
+
+    /// Returns `(observation, reward, done, legal_action_mask)`.
+    fn step(&mut self, action: usize) -> PyResult<(Vec<f32>, f32, bool, Vec<bool>)> {
+        let step = self
+            .env
+            .step(action)
+            .ok_or_else(|| PyValueError::new_err("Illegal action"))?;
+        let (observation, mask) = self.observation();
+        Ok((observation, step.reward, step.done, mask))
+    }
+
+    #[getter]
+    fn gameboard(&self) -> PyGameboard {
+        PyGameboard {
+            gameboard: self.env.gameboard().clone(),
+        }
+    }
+}
+
+#[pymodule]
+fn royal_game_of_ur(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyGameboard>()?;
+    m.add_class::<PyEnvironment>()?;
+    m.add("ACTIONS", ACTIONS)?;
+    m.add("OBSERVATION_SIZE", OBSERVATION_SIZE)?;
+    Ok(())
+}
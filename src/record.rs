@@ -0,0 +1,92 @@
+//! Game records, for reviewing a game after it ends.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::gameboard::Gameboard;
+use crate::rules::Ruleset;
+
+/// One decision: the position after the roll and what the player did.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Turn {
+    /// `Gameboard::to_notation` before the move, including the roll.
+    pub position: String,
+    /// Path cell of the piece moved, or `None` for a pass.
+    pub cell: Option<usize>,
+}
+
+/// Every turn of a game, in order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub ruleset: Ruleset,
+    pub turns: Vec<Turn>,
+}
+
+impl GameRecord {
+    pub fn new(ruleset: Ruleset) -> GameRecord {
+        GameRecord {
+            ruleset,
+            turns: Vec::new(),
+        }
+    }
+
+    /// Plays `cell` on `gameboard` and records it if the move is legal.
+    pub fn play(&mut self, gameboard: &mut Gameboard, cell: usize) -> bool {
+        let position = gameboard.to_notation();
+        let legal = gameboard._move(cell);
+        if legal {
+            self.turns.push(Turn {
+                position,
+                cell: Some(cell),
+            });
+        }
+        legal
+    }
+
+    /// Passes the turn on `gameboard` and records it.
+    pub fn pass(&mut self, gameboard: &mut Gameboard) {
+        self.turns.push(Turn {
+            position: gameboard.to_notation(),
+            cell: None,
+        });
+        gameboard.pass_turn();
+    }
+
+    /// Position before turn `i`'s move, or the final position for
+    /// `i == turns.len()`.
+    pub fn position(&self, i: usize) -> Option<Gameboard> {
+        if i > 0 && i == self.turns.len() {
+            let mut last = self.position(i - 1)?;
+            match self.turns[i - 1].cell {
+                Some(cell) => {
+                    last._move(cell);
+                }
+                None => last.pass_turn(),
+            }
+            return Some(last);
+        }
+        let turn = self.turns.get(i)?;
+        Gameboard::from_notation_with_ruleset(self.ruleset.clone(), &turn.position)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("record serializes")
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    /// Reads a record, checking its ruleset.
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<GameRecord> {
+        let json = fs::read_to_string(path)?;
+        let record: GameRecord = serde_json::from_str(&json)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        record
+            .ruleset
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(record)
+    }
+}
@@ -0,0 +1,139 @@
+//! Post-game blunder report.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::analysis;
+use crate::gameboard::Player;
+use crate::record::GameRecord;
+
+/// Moves losing more than this many percentage points of win probability
+/// are reported as blunders.
+pub const BLUNDER_THRESHOLD: f64 = 5.0;
+
+/// A move noticeably worse than the best available.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Blunder {
+    /// Index into the record's turns.
+    pub turn: usize,
+    pub player: Player,
+    /// Position before the move, including the roll.
+    pub position: String,
+    /// Path cell moved, or `None` for a pass.
+    pub played: Option<usize>,
+    /// Path cell of the best move.
+    pub best: usize,
+    /// Win probability lost, in percentage points.
+    pub loss: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    pub blunders: Vec<Blunder>,
+    /// 100 minus the average loss per decision, for the first and the second
+    /// player. Turns with a single legal move are not decisions.
+    pub accuracy: [f64; 2],
+}
+
+impl Report {
+    /// Reviews every turn of `record`, searching `depth` rolls ahead.
+    pub fn new(record: &GameRecord, depth: u32) -> Report {
+        let mut blunders = Vec::new();
+        let mut losses = [Vec::new(), Vec::new()];
+        for (i, turn) in record.turns.iter().enumerate() {
+            let gameboard = match record.position(i) {
+                Some(ref g) if g.dice_roll.is_some() => g.clone(),
+                _ => continue,
+            };
+            let analysis = analysis::analyse(&gameboard, depth);
+            let best = match analysis.moves.first() {
+                Some(best) => best,
+                None => continue,
+            };
+            let played = match turn.cell {
+                Some(_) if analysis.moves.len() < 2 => continue,
+                Some(cell) => match analysis.moves.iter().find(|m| m.cell == cell) {
+                    Some(played) => played.win_probability,
+                    None => continue,
+                },
+                None => {
+                    let mut passed = gameboard.clone();
+                    passed.pass_turn();
+                    1.0 - analysis::evaluate(&passed, depth.saturating_sub(1))
+                }
+            };
+            let loss = ((best.win_probability - played) * 100.0).max(0.0);
+            let player = gameboard.active_player;
+            losses[player_index(player)].push(loss);
+            if loss > BLUNDER_THRESHOLD {
+                blunders.push(Blunder {
+                    turn: i,
+                    player,
+                    position: turn.position.clone(),
+                    played: turn.cell,
+                    best: best.cell,
+                    loss,
+                });
+            }
+        }
+        let accuracy = |losses: &[f64]| {
+            if losses.is_empty() {
+                100.0
+            } else {
+                100.0 - losses.iter().sum::<f64>() / losses.len() as f64
+            }
+        };
+        Report {
+            blunders,
+            accuracy: [accuracy(&losses[0]), accuracy(&losses[1])],
+        }
+    }
+
+    pub fn accuracy(&self, player: Player) -> f64 {
+        self.accuracy[player_index(player)]
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Blunder report\n\n| Player | Accuracy |\n|---|---|\n");
+        out += &format!("| First | {:.1}% |\n", self.accuracy[0]);
+        out += &format!("| Second | {:.1}% |\n\n## Blunders\n\n", self.accuracy[1]);
+        if self.blunders.is_empty() {
+            out += "None.\n";
+            return out;
+        }
+        out += "| Turn | Player | Position | Played | Best | Loss |\n|---|---|---|---|---|---|\n";
+        for b in &self.blunders {
+            let played = b.played.map_or("pass".to_string(), |cell| cell.to_string());
+            out += &format!(
+                "| {} | {:?} | `{}` | {} | {} | {:.1} |\n",
+                b.turn + 1,
+                b.player,
+                b.position,
+                played,
+                b.best,
+                b.loss
+            );
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report serializes")
+    }
+
+    pub fn write_markdown<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_markdown())
+    }
+
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+}
+
+fn player_index(player: Player) -> usize {
+    match player {
+        Player::First => 0,
+        Player::Second => 1,
+    }
+}
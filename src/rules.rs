@@ -0,0 +1,230 @@
+//! Rule variants.
+
+use crate::dice::Dice;
+use crate::geometry::{BoardGeometry, Cell};
+
+/// Most pieces a player may have.
+pub const MAX_PIECES: u8 = 15;
+
+/// The five rosettes of the standard board.
+const ROSETTES: [Cell; 5] = [(0, 0), (0, 6), (1, 3), (2, 0), (2, 6)];
+
+/// Route the pieces take across the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Track {
+    /// Up the middle lane and straight back home.
+    #[default]
+    Standard,
+    /// Through the far end of the opponent's side before heading home.
+    Masters,
+}
+
+impl Track {
+    pub fn geometry(self) -> BoardGeometry {
+        match self {
+            Track::Standard => BoardGeometry::STANDARD,
+            Track::Masters => BoardGeometry::MASTERS,
+        }
+    }
+}
+
+/// When an event lets the mover roll again.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    Never,
+    Always,
+    /// Only on this roll, e.g. 4.
+    OnRoll(i8),
+}
+
+impl Trigger {
+    /// Whether the event grants another turn after rolling `roll`.
+    pub fn fires(self, roll: i8) -> bool {
+        match self {
+            Trigger::Never => false,
+            Trigger::Always => true,
+            Trigger::OnRoll(on) => on == roll,
+        }
+    }
+}
+
+/// Events that let the mover roll again, each with its own condition.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExtraTurns {
+    /// Landing on a rosette.
+    pub rosettes: Trigger,
+    pub captures: Trigger,
+    pub bearing_off: Trigger,
+}
+
+impl Default for ExtraTurns {
+    fn default() -> Self {
+        ExtraTurns {
+            rosettes: Trigger::Always,
+            captures: Trigger::Never,
+            bearing_off: Trigger::Never,
+        }
+    }
+}
+
+impl ExtraTurns {
+    /// Combinations offered by the frontends, the default first.
+    pub fn presets() -> Vec<ExtraTurns> {
+        let turns = |rosettes, captures, bearing_off| ExtraTurns {
+            rosettes,
+            captures,
+            bearing_off,
+        };
+        vec![
+            ExtraTurns::default(),
+            turns(Trigger::Always, Trigger::Always, Trigger::Never),
+            turns(Trigger::Always, Trigger::OnRoll(4), Trigger::Never),
+            turns(Trigger::OnRoll(4), Trigger::OnRoll(4), Trigger::Never),
+            turns(Trigger::Always, Trigger::Always, Trigger::Always),
+            turns(Trigger::Never, Trigger::Always, Trigger::Always),
+            turns(Trigger::Never, Trigger::Never, Trigger::Never),
+        ]
+    }
+
+    /// Short description such as `Rosette, capture on 4`.
+    pub fn label(&self) -> String {
+        let events = [
+            ("rosette", self.rosettes),
+            ("capture", self.captures),
+            ("bearing off", self.bearing_off),
+        ];
+        let parts: Vec<String> = events
+            .iter()
+            .filter_map(|&(event, trigger)| match trigger {
+                Trigger::Never => None,
+                Trigger::Always => Some(event.to_string()),
+                Trigger::OnRoll(roll) => Some(format!("{} on {}", event, roll)),
+            })
+            .collect();
+        if parts.is_empty() {
+            return "None".to_string();
+        }
+        let label = parts.join(", ");
+        label[..1].to_uppercase() + &label[1..]
+    }
+
+    fn triggers(&self) -> [Trigger; 3] {
+        [self.rosettes, self.captures, self.bearing_off]
+    }
+}
+
+/// Parameters of a rule variant. Build a custom one with struct update
+/// syntax from any preset.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ruleset {
+    pub name: String,
+    /// Pieces per player.
+    pub pieces: u8,
+    pub dice: Dice,
+    /// Marked squares, which may grant another turn by `extra_turns.rosettes`.
+    pub rosettes: Vec<Cell>,
+    /// Squares where a piece cannot be captured.
+    pub safe_squares: Vec<Cell>,
+    /// Saves from before tracks were configurable use the standard one.
+    #[serde(default)]
+    pub track: Track,
+    #[serde(default)]
+    pub extra_turns: ExtraTurns,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset::classic()
+    }
+}
+
+impl Ruleset {
+    /// This game's original rules: no extra turns and no safe squares.
+    pub fn classic() -> Ruleset {
+        Ruleset {
+            name: "Classic".to_string(),
+            pieces: 7,
+            dice: Dice::Binary(4),
+            rosettes: Vec::new(),
+            safe_squares: Vec::new(),
+            track: Track::Standard,
+            extra_turns: ExtraTurns::default(),
+        }
+    }
+
+    /// Irving Finkel's rules: rosettes grant another turn and the central
+    /// rosette is safe.
+    pub fn finkel() -> Ruleset {
+        Ruleset {
+            name: "Finkel".to_string(),
+            rosettes: ROSETTES.to_vec(),
+            safe_squares: vec![(1, 3)],
+            ..Ruleset::classic()
+        }
+    }
+
+    /// R.C. Bell's rules: every rosette grants another turn and is safe.
+    pub fn bell() -> Ruleset {
+        Ruleset {
+            name: "Bell".to_string(),
+            rosettes: ROSETTES.to_vec(),
+            safe_squares: ROSETTES.to_vec(),
+            ..Ruleset::classic()
+        }
+    }
+
+    /// James Masters' rules: Bell's rosettes on the longer Masters track.
+    pub fn masters() -> Ruleset {
+        Ruleset {
+            name: "Masters".to_string(),
+            track: Track::Masters,
+            ..Ruleset::bell()
+        }
+    }
+
+    /// Every preset, in menu order.
+    pub fn presets() -> Vec<Ruleset> {
+        vec![
+            Ruleset::classic(),
+            Ruleset::finkel(),
+            Ruleset::bell(),
+            Ruleset::masters(),
+        ]
+    }
+
+    /// Checks a ruleset read from a file, describing the first problem.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_PIECES).contains(&self.pieces) {
+            return Err(format!("{} pieces is out of range", self.pieces));
+        }
+        self.dice.validate()?;
+        let geometry = self.track.geometry();
+        let outcomes = self.dice.outcomes();
+        let max = outcomes.last().map_or(0, |&(roll, _)| roll as usize);
+        if !(1..geometry.path_len()).contains(&max) {
+            return Err(format!("highest roll {} does not fit the track", max));
+        }
+        for trigger in &self.extra_turns.triggers() {
+            if let Trigger::OnRoll(roll) = *trigger {
+                if roll < 1 || roll as usize > max {
+                    return Err(format!("extra turns need an impossible roll of {}", roll));
+                }
+            }
+        }
+        for &cell in self.rosettes.iter().chain(&self.safe_squares) {
+            if !geometry.is_square(cell) {
+                return Err(format!("{:?} is not on the board", cell));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_rosette(&self, cell: Cell) -> bool {
+        self.rosettes.contains(&cell)
+    }
+
+    pub fn is_safe(&self, cell: Cell) -> bool {
+        self.safe_squares.contains(&cell)
+    }
+}
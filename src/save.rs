@@ -0,0 +1,54 @@
+//! Saved games.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::clock::Clock;
+use crate::gameboard::Gameboard;
+use crate::rules::Ruleset;
+
+/// A game on disk: the ruleset it is played under, the position in
+/// `Gameboard::to_notation` form and the clocks of a timed game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub ruleset: Ruleset,
+    pub position: String,
+    #[serde(default)]
+    pub clock: Option<Clock>,
+}
+
+impl SaveGame {
+    pub fn new(gameboard: &Gameboard) -> SaveGame {
+        SaveGame {
+            ruleset: gameboard.ruleset.clone(),
+            position: gameboard.to_notation(),
+            clock: None,
+        }
+    }
+
+    /// Restores the game. Returns `None` if the ruleset is invalid or the
+    /// position does not fit it.
+    pub fn to_gameboard(&self) -> Option<Gameboard> {
+        self.ruleset.validate().ok()?;
+        Gameboard::from_notation_with_ruleset(self.ruleset.clone(), &self.position)
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<SaveGame> {
+        let json = fs::read_to_string(path)?;
+        let save: SaveGame = serde_json::from_str(&json)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(ref clock) = save.clock {
+            clock
+                .control
+                .validate()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(save)
+    }
+}
@@ -0,0 +1,392 @@
+//! Local HTTP + WebSocket service wrapping `Gameboard`.
+//!
+//! Routes:
+//!
+//! * `POST /games` - create a game. The body may give its rules and make it
+//!   timed: `{"ruleset": <rules::Ruleset>, "time_control": <clock::TimeControl>}`,
+//!   both optional.
+//! * `GET /games/<id>` - current state.
+//! * `POST /games/<id>/roll` - roll the dice.
+//! * `GET /games/<id>/moves` - legal moves for the current roll.
+//! * `POST /games/<id>/move` - move a piece, body `{"cell": <path cell>}`.
+//! * `POST /games/<id>/pass` - pass the turn.
+//! * `GET /games/<id>/events` - WebSocket; pushes an event after every change.
+//!
+//! Every successful response and event carries the full game state as JSON.
+//!
+//! Clocks run between requests: a player who runs out of time is moved for,
+//! or forfeits, at the next request for their game.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use tiny_http::{Header, Method, Request, Response, StatusCode};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use rand::rngs::StdRng;
+use rand::FromEntropy;
+
+use crate::clock::{self, Clock, TimeControl, Timeout};
+use crate::gameboard::{self, Gameboard};
+use crate::rules::Ruleset;
+
+/// Game state as sent to clients.
+#[derive(Serialize)]
+pub struct GameState {
+    pub id: u32,
+    #[serde(flatten)]
+    pub state: gameboard::GameState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock: Option<Clock>,
+}
+
+/// Message pushed to WebSocket subscribers.
+#[derive(Serialize)]
+struct Event<'a> {
+    event: &'a str,
+    state: GameState,
+}
+
+#[derive(Default, Deserialize)]
+struct CreateRequest {
+    ruleset: Option<Ruleset>,
+    time_control: Option<TimeControl>,
+}
+
+#[derive(Deserialize)]
+struct MoveRequest {
+    cell: usize,
+}
+
+struct Game {
+    gameboard: Gameboard,
+    rng: StdRng,
+    clock: Option<Clock>,
+    /// When the clock last ran.
+    ticked: Instant,
+    subscribers: Vec<Sender<String>>,
+}
+
+impl Game {
+    fn state(&self, id: u32) -> GameState {
+        let mut state = self.gameboard.state();
+        if let Some(winner) = self.clock.as_ref().and_then(Clock::winner) {
+            state.winner = Some(winner);
+            state.legal_moves.clear();
+        }
+        GameState {
+            id,
+            state,
+            clock: self.clock.clone(),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.gameboard.is_finished() || self.clock.as_ref().is_some_and(|c| c.winner().is_some())
+    }
+
+    /// Ends the move of `player`, restarting their move clock.
+    fn end_move(&mut self, player: gameboard::Player) {
+        if let Some(ref mut clock) = self.clock {
+            clock.end_move(player);
+        }
+    }
+
+    /// Charges the time since the last request to the player to move, and
+    /// moves for them or forfeits their game if it ran out. Returns whether
+    /// it did.
+    fn run_clock(&mut self) -> bool {
+        let elapsed = self.ticked.elapsed().as_secs_f64();
+        self.ticked = Instant::now();
+        let player = self.gameboard.active_player;
+        let timeout = match self.clock {
+            Some(ref mut clock) if !self.gameboard.is_finished() => {
+                if !clock.tick(player, elapsed) {
+                    return false;
+                }
+                clock.control.timeout
+            }
+            _ => return false,
+        };
+        if timeout == Timeout::Forfeit {
+            if let Some(ref mut clock) = self.clock {
+                clock.forfeited = Some(player);
+            }
+            return true;
+        }
+        if self.gameboard.dice_roll.is_none() {
+            self.gameboard.roll(&mut self.rng);
+        }
+        match clock::timeout_move(&self.gameboard, timeout, None, &mut self.rng) {
+            Some(cell) => {
+                self.gameboard._move(cell);
+            }
+            None => self.gameboard.pass_turn(),
+        }
+        self.end_move(player);
+        true
+    }
+
+    /// Sends an event to every subscriber, dropping the ones that hung up.
+    fn publish(&mut self, id: u32, event: &str) {
+        let message = to_json(&Event {
+            event,
+            state: self.state(id),
+        });
+        self.subscribers
+            .retain(|subscriber| subscriber.send(message.clone()).is_ok());
+    }
+}
+
+#[derive(Default)]
+struct Games {
+    next_id: u32,
+    games: HashMap<u32, Game>,
+}
+
+type ApiResult = Result<(u16, String), (u16, String)>;
+
+/// HTTP + WebSocket server owning every running game.
+pub struct Server {
+    http: tiny_http::Server,
+    games: Arc<Mutex<Games>>,
+}
+
+impl Server {
+    /// Binds the server. Use port 0 to pick an ephemeral port.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Server> {
+        let http = tiny_http::Server::http(addr).map_err(io::Error::other)?;
+        Ok(Server {
+            http,
+            games: Arc::new(Mutex::new(Games::default())),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.http
+            .server_addr()
+            .to_ip()
+            .expect("Server is bound to an IP address")
+    }
+
+    /// Serves requests until the process exits.
+    pub fn run(self) {
+        for request in self.http.incoming_requests() {
+            self.handle(request);
+        }
+    }
+
+    fn handle(&self, mut request: Request) {
+        let url = request.url().to_string();
+        let segments: Vec<&str> = url
+            .split('?')
+            .next()
+            .unwrap_or("")
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+        let method = request.method().clone();
+
+        if let (Method::Get, ["games", id, "events"]) = (&method, &segments[..]) {
+            self.subscribe(request, id);
+            return;
+        }
+
+        let mut body = String::new();
+        if request.as_reader().read_to_string(&mut body).is_err() {
+            respond(
+                request,
+                Err((400, "Could not read request body".to_string())),
+            );
+            return;
+        }
+
+        let result = match (&method, &segments[..]) {
+            (Method::Post, ["games"]) => self.create(&body),
+            (Method::Get, ["games", id]) => self.with_game(id, &body, |_, _| Ok("state")),
+            (Method::Get, ["games", id, "moves"]) => self.moves(id),
+            (Method::Post, ["games", id, "roll"]) => self.with_game(id, &body, |game, _| {
+                if game.is_finished() || game.gameboard.dice_roll.is_some() {
+                    return Err((409, "Cannot roll now".to_string()));
+                }
+                game.gameboard.roll(&mut game.rng);
+                Ok("roll")
+            }),
+            (Method::Post, ["games", id, "move"]) => self.with_game(id, &body, |game, body| {
+                let request: MoveRequest = serde_json::from_str(body)
+                    .map_err(|e| (400, format!("Invalid move request: {}", e)))?;
+                let player = game.gameboard.active_player;
+                if request.cell >= game.gameboard.geometry.path_len()
+                    || game.is_finished()
+                    || !game.gameboard._move(request.cell)
+                {
+                    return Err((409, "Illegal move".to_string()));
+                }
+                game.end_move(player);
+                Ok("move")
+            }),
+            (Method::Post, ["games", id, "pass"]) => self.with_game(id, &body, |game, _| {
+                if game.is_finished() {
+                    return Err((409, "Game is finished".to_string()));
+                }
+                let player = game.gameboard.active_player;
+                game.gameboard.pass_turn();
+                game.end_move(player);
+                Ok("pass")
+            }),
+            _ => Err((404, "Not found".to_string())),
+        };
+        respond(request, result);
+    }
+
+    fn create(&self, body: &str) -> ApiResult {
+        let request: CreateRequest = if body.trim().is_empty() {
+            CreateRequest::default()
+        } else {
+            serde_json::from_str(body).map_err(|e| (400, format!("Invalid game request: {}", e)))?
+        };
+        let ruleset = request.ruleset.unwrap_or_default();
+        ruleset
+            .validate()
+            .map_err(|e| (400, format!("Invalid ruleset: {}", e)))?;
+        if let Some(ref control) = request.time_control {
+            control
+                .validate()
+                .map_err(|e| (400, format!("Invalid time control: {}", e)))?;
+        }
+        let mut games = self.games.lock().unwrap();
+        let id = games.next_id;
+        games.next_id += 1;
+        let game = Game {
+            gameboard: Gameboard::with_ruleset(ruleset),
+            rng: StdRng::from_entropy(),
+            clock: request.time_control.map(Clock::new),
+            ticked: Instant::now(),
+            subscribers: Vec::new(),
+        };
+        let state = to_json(&game.state(id));
+        games.games.insert(id, game);
+        Ok((201, state))
+    }
+
+    fn moves(&self, id: &str) -> ApiResult {
+        let id = parse_id(id)?;
+        let mut games = self.games.lock().unwrap();
+        let game = games.games.get_mut(&id).ok_or_else(not_found)?;
+        if game.run_clock() {
+            game.publish(id, "timeout");
+        }
+        Ok((200, to_json(&game.state(id).state.legal_moves)))
+    }
+
+    /// Runs `action` on a game and publishes the event name it returns.
+    fn with_game<F>(&self, id: &str, body: &str, action: F) -> ApiResult
+    where
+        F: FnOnce(&mut Game, &str) -> Result<&'static str, (u16, String)>,
+    {
+        let id = parse_id(id)?;
+        let mut games = self.games.lock().unwrap();
+        let game = games.games.get_mut(&id).ok_or_else(not_found)?;
+        if game.run_clock() {
+            game.publish(id, "timeout");
+        }
+        let event = action(game, body)?;
+        if event != "state" {
+            game.publish(id, event);
+        }
+        Ok((200, to_json(&game.state(id))))
+    }
+
+    fn subscribe(&self, request: Request, id: &str) {
+        let key = request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Sec-WebSocket-Key"))
+            .map(|h| h.value.as_str().to_string());
+        let key = match key {
+            Some(key) => key,
+            None => {
+                respond(
+                    request,
+                    Err((400, "Expected a WebSocket upgrade".to_string())),
+                );
+                return;
+            }
+        };
+        let id = match parse_id(id) {
+            Ok(id) => id,
+            Err(e) => {
+                respond(request, Err(e));
+                return;
+            }
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        {
+            let mut games = self.games.lock().unwrap();
+            match games.games.get_mut(&id) {
+                Some(game) => {
+                    sender
+                        .send(to_json(&Event {
+                            event: "state",
+                            state: game.state(id),
+                        }))
+                        .ok();
+                    game.subscribers.push(sender);
+                }
+                None => {
+                    respond(request, Err(not_found()));
+                    return;
+                }
+            }
+        }
+
+        let response = Response::empty(StatusCode(101)).with_header(header(
+            "Sec-WebSocket-Accept",
+            &derive_accept_key(key.as_bytes()),
+        ));
+        let stream = request.upgrade("websocket", response);
+        thread::spawn(move || {
+            let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+            for message in receiver {
+                if socket.send(Message::Text(message)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+fn parse_id(id: &str) -> Result<u32, (u16, String)> {
+    id.parse().map_err(|_| not_found())
+}
+
+fn not_found() -> (u16, String) {
+    (404, "No such game".to_string())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("Game state is always serializable")
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).expect("Header is valid ASCII")
+}
+
+fn respond(request: Request, result: ApiResult) {
+    let (status, body) = match result {
+        Ok(ok) => ok,
+        Err((status, error)) => (status, to_json(&HashMap::from([("error", error)]))),
+    };
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"));
+    request.respond(response).ok();
+}
@@ -0,0 +1,164 @@
+//! User settings, kept in a TOML file in the user's config directory.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::bot::Bot;
+use crate::rules::{self, Ruleset};
+
+/// Smallest and largest window sizes accepted, in pixels.
+pub const MIN_WINDOW: [u32; 2] = [720, 432];
+pub const MAX_WINDOW: [u32; 2] = [7680, 4320];
+/// Longest a bot may be set to wait between actions, in seconds.
+pub const MAX_BOT_DELAY: f64 = 5.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Width and height of the window in pixels.
+    pub window: [u32; 2],
+    /// Name of the theme.
+    pub theme: String,
+    /// Seconds a bot waits before each roll and move.
+    pub bot_delay: f64,
+    /// `human` or `bot:<name>` for the first and the second player.
+    pub first_player: String,
+    pub second_player: String,
+    /// Name of a ruleset preset.
+    pub ruleset: String,
+    pub pieces: u8,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            window: MIN_WINDOW,
+            theme: "classic".to_string(),
+            bot_delay: 0.6,
+            first_player: "human".to_string(),
+            second_player: "human".to_string(),
+            ruleset: Ruleset::default().name,
+            pieces: Ruleset::default().pieces,
+        }
+    }
+}
+
+impl Settings {
+    /// `settings.toml` in `config_dir()`.
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("settings.toml"))
+    }
+
+    /// Reads the settings at `path()`. Falls back to the defaults if there
+    /// are none, or if the file cannot be read or is invalid.
+    pub fn load() -> Settings {
+        let path = match Settings::path() {
+            Some(path) => path,
+            None => return Settings::default(),
+        };
+        match Settings::read(&path) {
+            Ok(settings) => settings,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Settings::default(),
+            Err(e) => {
+                warn!("Ignoring {}: {}", path.display(), e);
+                Settings::default()
+            }
+        }
+    }
+
+    /// Writes the settings to `path()`, creating its directory.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Settings::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        self.write(path)
+    }
+
+    /// Checks every field, describing the first one out of range.
+    pub fn validate(&self) -> Result<(), String> {
+        let [width, height] = self.window;
+        if !(MIN_WINDOW[0]..=MAX_WINDOW[0]).contains(&width)
+            || !(MIN_WINDOW[1]..=MAX_WINDOW[1]).contains(&height)
+        {
+            return Err(format!("window size {}x{} is out of range", width, height));
+        }
+        if !(0.0..=MAX_BOT_DELAY).contains(&self.bot_delay) {
+            return Err(format!("bot delay {} is out of range", self.bot_delay));
+        }
+        for player in &[&self.first_player, &self.second_player] {
+            parse_player(player)?;
+        }
+        if !(1..=rules::MAX_PIECES).contains(&self.pieces) {
+            return Err(format!("{} pieces is out of range", self.pieces));
+        }
+        if self.ruleset().is_none() {
+            return Err(format!("unknown ruleset \"{}\"", self.ruleset));
+        }
+        Ok(())
+    }
+
+    /// Bots seated by default, first player then second; `None` is human.
+    pub fn players(&self) -> [Option<Bot>; 2] {
+        [
+            parse_player(&self.first_player).unwrap_or(None),
+            parse_player(&self.second_player).unwrap_or(None),
+        ]
+    }
+
+    /// The preset named by `ruleset` with `pieces` pieces, if valid.
+    pub fn ruleset(&self) -> Option<Ruleset> {
+        Ruleset::presets()
+            .into_iter()
+            .find(|r| r.name == self.ruleset)
+            .map(|r| Ruleset {
+                pieces: self.pieces,
+                ..r
+            })
+            .filter(|r| r.validate().is_ok())
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let toml = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, toml)
+    }
+
+    /// Reads and validates settings. Missing fields take their defaults.
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Settings> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let toml = fs::read_to_string(path)?;
+        let settings: Settings = toml::from_str(&toml).map_err(|e| invalid(e.to_string()))?;
+        settings.validate().map_err(invalid)?;
+        Ok(settings)
+    }
+}
+
+/// The game's directory in the platform's config directory, if it has one.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("royal_game_of_ur"))
+}
+
+/// Where the game keeps its data file `name`, such as the saved game: in
+/// `config_dir()`, or in the working directory if there is none.
+pub fn data_path(name: &str) -> PathBuf {
+    config_dir().map_or_else(|| PathBuf::from(name), |dir| dir.join(name))
+}
+
+/// Parses `human` as `None` and `bot:<name>` as that bot.
+pub fn parse_player(player: &str) -> Result<Option<Bot>, String> {
+    if player == "human" {
+        return Ok(None);
+    }
+    player
+        .strip_prefix("bot:")
+        .and_then(Bot::from_name)
+        .map(Some)
+        .ok_or_else(|| format!("unknown player \"{}\"", player))
+}
+
+/// The `parse_player` form of `player`.
+pub fn player_name(player: Option<Bot>) -> String {
+    player.map_or("human".to_string(), |bot| format!("bot:{}", bot.name()))
+}
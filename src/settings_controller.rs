@@ -0,0 +1,158 @@
+//! Settings screen controller.
+
+use piston::input::{GenericEvent, Key};
+
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::settings::{self, Settings};
+
+use crate::form::{self, Form, FormEvent};
+use crate::menu_controller::{self, PIECE_COUNTS};
+
+/// Window sizes offered, smallest first.
+pub const WINDOW_SIZES: [[u32; 2]; 4] = [[720, 432], [960, 576], [1200, 720], [1440, 864]];
+/// Bot delays offered, in seconds.
+pub const BOT_DELAYS: [f64; 4] = [0.0, 0.3, 0.6, 1.2];
+
+/// A row of the settings screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SettingsRow {
+    Window,
+    Theme,
+    BotDelay,
+    FirstPlayer,
+    SecondPlayer,
+    Ruleset,
+    Pieces,
+}
+
+impl SettingsRow {
+    pub const ALL: [SettingsRow; 7] = [
+        SettingsRow::Window,
+        SettingsRow::Theme,
+        SettingsRow::BotDelay,
+        SettingsRow::FirstPlayer,
+        SettingsRow::SecondPlayer,
+        SettingsRow::Ruleset,
+        SettingsRow::Pieces,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingsRow::Window => "Window size",
+            SettingsRow::Theme => "Theme",
+            SettingsRow::BotDelay => "Bot delay",
+            SettingsRow::FirstPlayer => "First player",
+            SettingsRow::SecondPlayer => "Second player",
+            SettingsRow::Ruleset => "Rules",
+            SettingsRow::Pieces => "Pieces",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SettingsAction {
+    Save,
+    Back,
+}
+
+impl SettingsAction {
+    pub const ALL: [SettingsAction; 2] = [SettingsAction::Save, SettingsAction::Back];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingsAction::Save => "Save",
+            SettingsAction::Back => "Back",
+        }
+    }
+}
+
+pub struct SettingsController {
+    pub settings: Settings,
+    /// Names of the themes to pick from.
+    pub themes: Vec<String>,
+    pub form: Form,
+    /// Set when a button is clicked, for the caller to act on.
+    pub action: Option<SettingsAction>,
+}
+
+impl SettingsController {
+    pub fn new(settings: Settings, themes: Vec<String>) -> SettingsController {
+        SettingsController {
+            settings,
+            themes,
+            form: Form::new(),
+            action: None,
+        }
+    }
+
+    /// Text shown for `row`'s current value.
+    pub fn value(&self, row: SettingsRow) -> String {
+        let settings = &self.settings;
+        match row {
+            SettingsRow::Window => format!("{} x {}", settings.window[0], settings.window[1]),
+            SettingsRow::Theme => settings.theme.clone(),
+            SettingsRow::BotDelay => format!("{:.1} s", settings.bot_delay),
+            SettingsRow::FirstPlayer => menu_controller::player_label(settings.players()[0]),
+            SettingsRow::SecondPlayer => menu_controller::player_label(settings.players()[1]),
+            SettingsRow::Ruleset => settings.ruleset.clone(),
+            SettingsRow::Pieces => settings.pieces.to_string(),
+        }
+    }
+
+    /// Steps `row` to its next value, or its previous one if `!forward`.
+    pub fn cycle(&mut self, row: SettingsRow, forward: bool) {
+        let settings = &mut self.settings;
+        match row {
+            SettingsRow::Window => {
+                let i = form::step_choice(&WINDOW_SIZES, &settings.window, 0, forward);
+                settings.window = WINDOW_SIZES[i];
+            }
+            SettingsRow::Theme if !self.themes.is_empty() => {
+                let i = form::step_choice(&self.themes, &settings.theme, 0, forward);
+                settings.theme = self.themes[i].clone();
+            }
+            SettingsRow::Theme => {}
+            SettingsRow::BotDelay => {
+                let i = form::step_choice(&BOT_DELAYS, &settings.bot_delay, 2, forward);
+                settings.bot_delay = BOT_DELAYS[i];
+            }
+            SettingsRow::FirstPlayer | SettingsRow::SecondPlayer => {
+                let side = if row == SettingsRow::FirstPlayer {
+                    0
+                } else {
+                    1
+                };
+                let choices = menu_controller::player_choices();
+                let i = form::step_choice(&choices, &settings.players()[side], 0, forward);
+                let name = settings::player_name(choices[i]);
+                if side == 0 {
+                    settings.first_player = name;
+                } else {
+                    settings.second_player = name;
+                }
+            }
+            SettingsRow::Ruleset => {
+                let names: Vec<String> = Ruleset::presets().into_iter().map(|r| r.name).collect();
+                let i = form::step_choice(&names, &settings.ruleset, 0, forward);
+                settings.ruleset = names[i].clone();
+            }
+            SettingsRow::Pieces => {
+                let i = form::step_choice(&PIECE_COUNTS, &settings.pieces, 2, forward);
+                settings.pieces = PIECE_COUNTS[i];
+            }
+        }
+    }
+
+    pub fn event<E: GenericEvent>(&mut self, e: &E) {
+        match self
+            .form
+            .event(e, SettingsRow::ALL.len(), SettingsAction::ALL.len())
+        {
+            Some(FormEvent::Step(row, forward)) => self.cycle(SettingsRow::ALL[row], forward),
+            Some(FormEvent::Button(button)) => self.action = Some(SettingsAction::ALL[button]),
+            Some(FormEvent::Key(Key::Return)) => self.action = Some(SettingsAction::Save),
+            Some(FormEvent::Key(Key::Backspace)) => self.action = Some(SettingsAction::Back),
+            _ => {}
+        }
+    }
+}
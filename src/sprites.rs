@@ -0,0 +1,73 @@
+//! Optional sprites for the board, pieces and dice.
+//!
+//! Every sprite is a PNG in the `sprites` directory of the assets:
+//!
+//! - `tile.png`: a plain square of a side lane.
+//! - `eyes.png`: a square of the shared lane, traditionally decorated with
+//!   eye patterns.
+//! - `rosette.png`: a rosette square.
+//! - `piece_1.png` and `piece_2.png`: the first and second player's pieces.
+//! - `die_marked.png` and `die_blank.png`: a die showing a marked tip, which
+//!   counts one, or a blank one.
+//!
+//! Missing sprites are drawn with primitives instead, each on its own.
+
+use std::path::Path;
+
+use opengl_graphics::{Texture, TextureSettings};
+
+/// Asset directory the sprites are loaded from.
+pub const SPRITES_DIR: &str = "sprites";
+
+pub struct Sprites<T> {
+    pub tile: Option<T>,
+    pub eyes: Option<T>,
+    pub rosette: Option<T>,
+    /// First player's, then second player's.
+    pub pieces: [Option<T>; 2],
+    pub die_marked: Option<T>,
+    pub die_blank: Option<T>,
+}
+
+/// No sprites, so everything is drawn with primitives.
+impl<T> Default for Sprites<T> {
+    fn default() -> Self {
+        Sprites {
+            tile: None,
+            eyes: None,
+            rosette: None,
+            pieces: [None, None],
+            die_marked: None,
+            die_blank: None,
+        }
+    }
+}
+
+impl Sprites<Texture> {
+    /// Loads every sprite found in `dir`. Needs a current OpenGL context.
+    pub fn load<P: AsRef<Path>>(dir: P) -> Sprites<Texture> {
+        let dir = dir.as_ref();
+        let settings = TextureSettings::new();
+        let load = |name: &str| {
+            let path = dir.join(name);
+            if !path.exists() {
+                return None;
+            }
+            match Texture::from_path(&path, &settings) {
+                Ok(texture) => Some(texture),
+                Err(e) => {
+                    warn!("Could not load sprite {}: {}", path.display(), e);
+                    None
+                }
+            }
+        };
+        Sprites {
+            tile: load("tile.png"),
+            eyes: load("eyes.png"),
+            rosette: load("rosette.png"),
+            pieces: [load("piece_1.png"), load("piece_2.png")],
+            die_marked: load("die_marked.png"),
+            die_blank: load("die_blank.png"),
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! Endgame tablebases.
+//!
+//! A tablebase holds the exact win probability of every classic-rules
+//! position where neither side has more than `max_remaining` pieces left to
+//! bear off. Captures only send pieces back to the reserve, so that set of
+//! positions is closed under play and is solved by value iteration.
+//!
+//! Files are gzip-compressed: a header followed by one 16-bit probability per
+//! position, in the order `positions` enumerates them.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::bitboard::{self, Position, SHARED_MASK};
+use crate::dice::Dice;
+use crate::gameboard::Gameboard;
+use crate::rules::{self, Ruleset};
+
+const MAGIC: &[u8; 4] = b"URTB";
+const VERSION: u8 = 1;
+/// Iteration stops once no value moves by more than this.
+const TOLERANCE: f64 = 1e-12;
+
+/// Solved endgame positions.
+pub struct Tablebase {
+    pieces: u8,
+    max_remaining: u8,
+    /// `Position::index` of every position, sorted, with `values` alongside.
+    keys: Vec<u64>,
+    /// Probability that the side to move wins, before rolling.
+    values: Vec<f64>,
+}
+
+impl Tablebase {
+    /// Solves every position with at most `max_remaining` of `pieces` pieces
+    /// left per side. `progress` is called after each sweep with the sweep
+    /// number and the largest change in any value.
+    pub fn generate<F: FnMut(usize, f64)>(
+        pieces: u8,
+        max_remaining: u8,
+        mut progress: F,
+    ) -> Tablebase {
+        let positions = positions(pieces, max_remaining);
+        let slots: HashMap<u64, u32> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.index(), i as u32))
+            .collect();
+        // Successors of every position for every roll, flattened: `children`
+        // holds slots, or `WIN` for a move that ends the game, and
+        // `offsets[i * rolls + r]` is where position `i`'s roll `r` starts.
+        const WIN: u32 = u32::MAX;
+        let outcomes = Dice::Binary(4).outcomes();
+        let mut offsets = vec![0];
+        let mut children = Vec::new();
+        for position in &positions {
+            for &(roll, _) in &outcomes {
+                children.extend(
+                    successors(position, roll as u8)
+                        .into_iter()
+                        .map(|child| child.map_or(WIN, |c| slots[&c.index()])),
+                );
+                offsets.push(children.len());
+            }
+        }
+
+        let mut values = vec![0.5; positions.len()];
+        let mut sweep = 0;
+        loop {
+            sweep += 1;
+            let mut largest = 0.0f64;
+            for i in 0..values.len() {
+                let mut value = 0.0;
+                for (r, &(_, p)) in outcomes.iter().enumerate() {
+                    let k = i * outcomes.len() + r;
+                    let best = children[offsets[k]..offsets[k + 1]]
+                        .iter()
+                        .map(|&c| {
+                            if c == WIN {
+                                1.0
+                            } else {
+                                1.0 - values[c as usize]
+                            }
+                        })
+                        .fold(0.0, f64::max);
+                    value += p * best;
+                }
+                largest = largest.max((value - values[i]).abs());
+                values[i] = value;
+            }
+            progress(sweep, largest);
+            if largest < TOLERANCE {
+                break;
+            }
+        }
+        Tablebase::from_values(pieces, max_remaining, &positions, values)
+    }
+
+    fn from_values(
+        pieces: u8,
+        max_remaining: u8,
+        positions: &[Position],
+        values: Vec<f64>,
+    ) -> Tablebase {
+        let mut entries: Vec<(u64, f64)> =
+            positions.iter().map(|p| p.index()).zip(values).collect();
+        entries.sort_by_key(|e| e.0);
+        Tablebase {
+            pieces,
+            max_remaining,
+            keys: entries.iter().map(|e| e.0).collect(),
+            values: entries.iter().map(|e| e.1).collect(),
+        }
+    }
+
+    pub fn pieces(&self) -> u8 {
+        self.pieces
+    }
+
+    pub fn max_remaining(&self) -> u8 {
+        self.max_remaining
+    }
+
+    /// Number of positions stored.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Probability that the side to move in `position` wins, before rolling.
+    pub fn probe(&self, position: &Position) -> Option<f64> {
+        if position.pieces() != self.pieces {
+            return None;
+        }
+        self.keys
+            .binary_search(&position.index())
+            .ok()
+            .map(|i| self.values[i])
+    }
+
+    /// `probe` for a board that has not rolled yet, if its rules match the
+    /// ones the tablebase was solved under.
+    pub fn probe_gameboard(&self, gameboard: &Gameboard) -> Option<f64> {
+        if gameboard.dice_roll.is_some() || !self.supports(&gameboard.ruleset) {
+            return None;
+        }
+        self.probe(&Position::try_from(gameboard).ok()?)
+    }
+
+    /// Whether `ruleset` plays like the classic rules with this tablebase's
+    /// piece count.
+    pub fn supports(&self, ruleset: &Ruleset) -> bool {
+        ruleset.pieces == self.pieces
+            && ruleset.dice == Dice::Binary(4)
+            && bitboard::supports(ruleset)
+    }
+
+    /// Largest difference between a stored value and one step of the game's
+    /// recurrence applied to the stored values. Close to zero for a sound
+    /// table, up to the rounding of the file format.
+    pub fn verify(&self) -> f64 {
+        positions(self.pieces, self.max_remaining)
+            .iter()
+            .map(|position| {
+                let stored = self.probe(position).unwrap_or(f64::NAN);
+                let expected = backup(position, |p| self.probe(p).unwrap_or(f64::NAN));
+                let error = (stored - expected).abs();
+                // A missing position is as bad as it gets.
+                if error.is_nan() {
+                    f64::INFINITY
+                } else {
+                    error
+                }
+            })
+            .fold(0.0, f64::max)
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = GzEncoder::new(File::create(path)?, Compression::best());
+        out.write_all(MAGIC)?;
+        out.write_all(&[VERSION, self.pieces, self.max_remaining])?;
+        for position in positions(self.pieces, self.max_remaining) {
+            let value = self.probe(&position).unwrap_or(0.0);
+            let quantized = (value * f64::from(u16::MAX)).round() as u16;
+            out.write_all(&quantized.to_le_bytes())?;
+        }
+        out.finish()?;
+        Ok(())
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Tablebase> {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut input = GzDecoder::new(File::open(path)?);
+        let mut header = [0; 7];
+        input.read_exact(&mut header)?;
+        if &header[..4] != MAGIC || header[4] != VERSION {
+            return Err(invalid("not a tablebase file"));
+        }
+        let (pieces, max_remaining) = (header[5], header[6]);
+        if max_remaining == 0 || max_remaining > pieces || pieces > rules::MAX_PIECES {
+            return Err(invalid("tablebase file has an impossible piece count"));
+        }
+        let positions = positions(pieces, max_remaining);
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes)?;
+        if bytes.len() != positions.len() * 2 {
+            return Err(invalid("tablebase file is truncated"));
+        }
+        let values = bytes
+            .chunks(2)
+            .map(|b| f64::from(u16::from_le_bytes([b[0], b[1]])) / f64::from(u16::MAX))
+            .collect();
+        Ok(Tablebase::from_values(
+            pieces,
+            max_remaining,
+            &positions,
+            values,
+        ))
+    }
+}
+
+/// Win probability for the side to move in `position` before rolling, given
+/// `value` for the positions it can reach.
+fn backup<F: Fn(&Position) -> f64>(position: &Position, value: F) -> f64 {
+    Dice::Binary(4)
+        .outcomes()
+        .into_iter()
+        .map(|(roll, p)| {
+            let best = successors(position, roll as u8)
+                .iter()
+                .map(|child| child.as_ref().map_or(1.0, |c| 1.0 - value(c)))
+                .fold(0.0, f64::max);
+            p * best
+        })
+        .sum()
+}
+
+/// Positions the side to move can reach with `roll`, passing if there is no
+/// move. `None` stands for a move that wins the game.
+fn successors(position: &Position, roll: u8) -> Vec<Option<Position>> {
+    let mover = position.side_to_move();
+    let moves = position.moves(roll);
+    if moves == 0 {
+        let mut passed = *position;
+        passed.pass();
+        return vec![Some(passed)];
+    }
+    (0..15)
+        .filter(|&from| moves & 1 << from != 0)
+        .map(|from| {
+            let mut child = *position;
+            child.make_move(from, roll);
+            if child.winner() == Some(mover) {
+                None
+            } else {
+                Some(child)
+            }
+        })
+        .collect()
+}
+
+/// Every unfinished position with at most `max_remaining` of `pieces` pieces
+/// left per side, in a fixed order.
+pub fn positions(pieces: u8, max_remaining: u8) -> Vec<Position> {
+    // Each side's layouts as (lane, reserve) pairs.
+    let mut sides = Vec::new();
+    for lane in 0u16..1 << 14 {
+        let lane = lane << 1;
+        let on_board = lane.count_ones() as u8;
+        for remaining in on_board.max(1)..=max_remaining.min(pieces) {
+            sides.push((lane, remaining - on_board));
+        }
+    }
+    let mut positions = Vec::new();
+    for side in 0..2 {
+        for &(lane_1, reserve_1) in &sides {
+            for &(lane_2, reserve_2) in &sides {
+                if lane_1 & lane_2 & SHARED_MASK != 0 {
+                    continue;
+                }
+                let remaining_1 = lane_1.count_ones() as u8 + reserve_1;
+                let remaining_2 = lane_2.count_ones() as u8 + reserve_2;
+                positions.push(Position {
+                    lanes: [lane_1, lane_2],
+                    reserve: [reserve_1, reserve_2],
+                    finished: [pieces - remaining_1, pieces - remaining_2],
+                    side,
+                    pieces,
+                });
+            }
+        }
+    }
+    positions
+}
@@ -0,0 +1,144 @@
+//! Colour themes for the frontends.
+//!
+//! Themes are read from TOML: a `[[theme]]` table per theme with a name,
+//! every colour of the board and a shape for each player's pieces. The
+//! built-in ones live in `assets/themes.toml`, and users can add their own
+//! in a `themes.toml` next to their settings.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::assets;
+use crate::settings;
+
+/// Red, green, blue and alpha, each from 0 to 1.
+pub type Color = [f32; 4];
+
+const BUILTIN: &str = include_str!("../assets/themes.toml");
+/// File of extra themes, among the assets or in the user's data.
+pub const THEMES_FILE: &str = "themes.toml";
+
+/// How a player's pieces are drawn, so they differ by more than colour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PieceShape {
+    #[default]
+    Dot,
+    Square,
+    Diamond,
+    Triangle,
+    Ring,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    /// Behind everything else.
+    pub window_color: Color,
+    pub background_color: Color,
+    pub border_color: Color,
+    /// Also used for text.
+    pub board_edge_color: Color,
+    pub section_edge_color: Color,
+    pub cell_edge_color: Color,
+    pub player_1_color: Color,
+    pub player_2_color: Color,
+    pub player_1_board_color: Color,
+    pub player_2_board_color: Color,
+    pub rosette_color: Color,
+    pub focus_color: Color,
+    #[serde(default)]
+    pub player_1_shape: PieceShape,
+    #[serde(default)]
+    pub player_2_shape: PieceShape,
+}
+
+#[derive(Deserialize)]
+struct ThemeFile {
+    theme: Vec<Theme>,
+}
+
+impl Default for Theme {
+    /// The classic theme.
+    fn default() -> Self {
+        Theme::presets().remove(0)
+    }
+}
+
+impl Theme {
+    /// The built-in themes: classic, high contrast, deuteranopia and dark.
+    pub fn presets() -> Vec<Theme> {
+        Theme::parse(BUILTIN).expect("built-in themes are valid")
+    }
+
+    /// The presets, then the themes in the assets' and the user's
+    /// `themes.toml`, each replacing any of the same name. Files that
+    /// cannot be read are skipped with a warning.
+    pub fn load() -> Vec<Theme> {
+        let mut themes = Theme::presets();
+        let files = assets::find(THEMES_FILE)
+            .into_iter()
+            .chain(Some(settings::data_path(THEMES_FILE)));
+        for path in files {
+            match Theme::read(&path) {
+                Ok(extra) => merge(&mut themes, extra),
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => warn!("Could not load {}: {}", path.display(), e),
+            }
+        }
+        themes
+    }
+
+    /// Reads every theme in a TOML document. Fails on missing fields and on
+    /// colour components outside 0 to 1.
+    pub fn parse(toml: &str) -> Result<Vec<Theme>, String> {
+        let file: ThemeFile = toml::from_str(toml).map_err(|e| e.to_string())?;
+        for theme in &file.theme {
+            if !theme
+                .colors()
+                .iter()
+                .flatten()
+                .all(|c| (0.0..=1.0).contains(c))
+            {
+                return Err(format!(
+                    "theme \"{}\" has a colour outside 0 to 1",
+                    theme.name
+                ));
+            }
+        }
+        Ok(file.theme)
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Vec<Theme>> {
+        let toml = fs::read_to_string(path)?;
+        Theme::parse(&toml).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn colors(&self) -> [Color; 12] {
+        [
+            self.window_color,
+            self.background_color,
+            self.border_color,
+            self.board_edge_color,
+            self.section_edge_color,
+            self.cell_edge_color,
+            self.player_1_color,
+            self.player_2_color,
+            self.player_1_board_color,
+            self.player_2_board_color,
+            self.rosette_color,
+            self.focus_color,
+        ]
+    }
+}
+
+/// Adds `extra` to `themes`, replacing any theme with the same name.
+pub fn merge(themes: &mut Vec<Theme>, extra: Vec<Theme>) {
+    for theme in extra {
+        match themes.iter_mut().find(|t| t.name == theme.name) {
+            Some(existing) => *existing = theme,
+            None => themes.push(theme),
+        }
+    }
+}
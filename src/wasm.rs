@@ -0,0 +1,68 @@
+//! JavaScript bindings for the rules engine.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use wasm_bindgen::prelude::*;
+
+use crate::bot;
+use crate::gameboard::{Gameboard, Player};
+
+/// A game with its own seeded dice.
+#[wasm_bindgen]
+pub struct Game {
+    gameboard: Gameboard,
+    rng: StdRng,
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Starts a new game. The same seed always produces the same rolls.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32) -> Game {
+        Game {
+            gameboard: Gameboard::new(),
+            rng: StdRng::seed_from_u64(u64::from(seed)),
+        }
+    }
+
+    /// Full state as JSON, in the same shape the HTTP service returns.
+    pub fn state(&self) -> String {
+        serde_json::to_string(&self.gameboard.state()).expect("Game state is always serializable")
+    }
+
+    /// Rolls the dice and returns the total.
+    pub fn roll(&mut self) -> i8 {
+        self.gameboard.roll(&mut self.rng)
+    }
+
+    /// Path cells that can move with the current roll.
+    pub fn legal_moves(&self) -> Vec<u32> {
+        self.gameboard
+            .legal_moves()
+            .into_iter()
+            .map(|cell| cell as u32)
+            .collect()
+    }
+
+    /// Moves the piece on path cell `cell`. Returns `false` if the move is illegal.
+    pub fn apply_move(&mut self, cell: u32) -> bool {
+        (cell as usize) < self.gameboard.geometry.path_len() && self.gameboard._move(cell as usize)
+    }
+
+    pub fn pass_turn(&mut self) {
+        self.gameboard.pass_turn();
+    }
+
+    /// The move the computer opponent would play.
+    pub fn bot_move(&self) -> Option<u32> {
+        bot::choose_move(&self.gameboard).map(|cell| cell as u32)
+    }
+
+    /// 1 or 2 once the game is won.
+    pub fn winner(&self) -> Option<u8> {
+        self.gameboard.winner().map(|player| match player {
+            Player::First => 1,
+            Player::Second => 2,
+        })
+    }
+}
@@ -0,0 +1,52 @@
+extern crate royal_game_of_ur;
+
+use std::time::Instant;
+
+use royal_game_of_ur::analysis::{analyse, DEFAULT_DEPTH};
+use royal_game_of_ur::{Gameboard, Player};
+
+#[test]
+fn best_move_ranks_first() {
+    // Capturing on path cell 8 beats advancing the back piece.
+    let g = Gameboard::from_notation("5100001000000000/6000000010000000 1 2").unwrap();
+    let analysis = analyse(&g, DEFAULT_DEPTH);
+    assert_eq!(analysis.moves.len(), 3);
+    assert_eq!(analysis.moves[0].cell, 6);
+    assert!(analysis.moves[0].win_probability > analysis.moves[1].win_probability);
+
+    // Bearing off the last piece wins outright.
+
+    let g = Gameboard::from_notation("0000000000000106/1000000000000006 1 2").unwrap();
+    let analysis = analyse(&g, DEFAULT_DEPTH);
+    assert_eq!(analysis.moves[0].win_probability, 1.0);
+    assert_eq!(analysis.win_probability(Player::First), 1.0);
+}
+
+#[test]
+fn probabilities_are_consistent() {
+    let g = Gameboard::from_notation("3101000100000010/2000110011000001 2 -").unwrap();
+    let before = analyse(&g, DEFAULT_DEPTH);
+    assert!(before.moves.is_empty());
+    assert!(before.first_player > 0.0 && before.first_player < 1.0);
+
+    // The evaluation before the roll is the dice-weighted best move after it.
+    let expected: f64 = g
+        .ruleset
+        .dice
+        .outcomes()
+        .into_iter()
+        .map(|(roll, p)| {
+            let mut rolled = g.clone();
+            rolled.dice_roll = Some(roll);
+            p * analyse(&rolled, DEFAULT_DEPTH).win_probability(Player::Second)
+        })
+        .sum();
+    assert!((before.win_probability(Player::Second) - expected).abs() < 1e-9);
+}
+
+#[test]
+fn default_depth_is_interactive() {
+    let start = Instant::now();
+    analyse(&Gameboard::new(), DEFAULT_DEPTH);
+    assert!(start.elapsed().as_millis() < 500);
+}
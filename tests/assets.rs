@@ -0,0 +1,50 @@
+extern crate royal_game_of_ur;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use royal_game_of_ur::assets::{self, ASSETS_ENV};
+
+#[test]
+fn assets_are_found_in_order() {
+    // Tests run from the repository root, so its assets are the last resort.
+    assert_eq!(
+        assets::find("FiraSans-Regular.ttf"),
+        Some(PathBuf::from("assets/FiraSans-Regular.ttf"))
+    );
+    assert_eq!(assets::find("missing.png"), None);
+
+    let dir = env::temp_dir().join("royal_game_of_ur_assets_test");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("FiraSans-Regular.ttf"), b"").unwrap();
+    env::set_var(ASSETS_ENV, &dir);
+    let dirs = assets::search_dirs();
+    assert_eq!(dirs[1], dir);
+    assert_eq!(dirs.last(), Some(&PathBuf::from("assets")));
+    assert_eq!(
+        assets::find("FiraSans-Regular.ttf"),
+        Some(dir.join("FiraSans-Regular.ttf"))
+    );
+}
+
+#[test]
+fn sprites_are_shipped() {
+    let dir = PathBuf::from("assets/sprites");
+    for name in &[
+        "tile.png",
+        "eyes.png",
+        "rosette.png",
+        "piece_1.png",
+        "piece_2.png",
+        "die_marked.png",
+        "die_blank.png",
+    ] {
+        let png = fs::read(dir.join(name)).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n", "{} is not a PNG", name);
+        // The image header holds the width then the height.
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..20], &png[20..24], "{} is not square", name);
+    }
+    assert!(dir.join("LICENCE").exists());
+}
@@ -0,0 +1,108 @@
+extern crate rand;
+extern crate royal_game_of_ur;
+
+use std::convert::TryFrom;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use royal_game_of_ur::bitboard::{index_count, Position, INDEX_COUNT};
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::Gameboard;
+
+fn mask(moves: &[usize]) -> u16 {
+    moves.iter().fold(0, |mask, &cell| mask | 1 << cell)
+}
+
+fn play_random_games(pieces: u8, seed: u64) {
+    let ruleset = Ruleset {
+        pieces,
+        ..Ruleset::classic()
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..50 {
+        let mut gameboard = Gameboard::with_ruleset(ruleset.clone());
+        let mut position = Position::with_pieces(pieces);
+        while !gameboard.is_finished() {
+            assert_eq!(Position::try_from(&gameboard), Ok(position));
+            let index = position.index();
+            assert!(index < index_count(pieces));
+            assert_eq!(
+                Position::from_index_with_pieces(pieces, index),
+                Some(position)
+            );
+            assert_eq!(
+                position.to_gameboard().to_notation(),
+                gameboard.to_notation()
+            );
+
+            let roll = gameboard.roll(&mut rng);
+            let moves = gameboard.legal_moves();
+            assert_eq!(position.moves(roll as u8), mask(&moves));
+            if moves.is_empty() {
+                gameboard.pass_turn();
+                position.pass();
+                continue;
+            }
+
+            let from = moves[rng.gen_range(0, moves.len())];
+            let before = position;
+            let undo = position.make_move(from as u8, roll as u8);
+            let after = position;
+            position.unmake_move(undo);
+            assert_eq!(position, before);
+            position = after;
+            assert!(gameboard._move(from));
+        }
+        assert_eq!(position.winner(), gameboard.winner());
+        assert_eq!(position.moves(4), 0);
+    }
+}
+
+#[test]
+fn matches_gameboard_over_random_games() {
+    play_random_games(7, 3);
+}
+
+#[test]
+fn matches_gameboard_with_other_piece_counts() {
+    play_random_games(3, 4);
+    play_random_games(10, 6);
+}
+
+#[test]
+fn index_round_trips() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut position = Position::new();
+    for _ in 0..10_000 {
+        let index = position.index();
+        assert!(index < INDEX_COUNT);
+        assert_eq!(Position::from_index(index), Some(position));
+
+        let roll = (0..4).map(|_| rng.gen_range(0, 2)).sum();
+        let moves = position.moves(roll);
+        if position.winner().is_some() {
+            position = Position::new();
+        } else if moves == 0 {
+            position.pass();
+        } else {
+            let from = (0..15).rev().find(|&i| moves & 1 << i != 0).unwrap();
+            position.make_move(from, roll);
+        }
+    }
+    assert_eq!(Position::from_index(INDEX_COUNT), None);
+}
+
+#[test]
+fn only_classic_boards_convert() {
+    for ruleset in Ruleset::presets() {
+        let gameboard = Gameboard::with_ruleset(ruleset.clone());
+        let converted = Position::try_from(&gameboard);
+        assert_eq!(
+            converted.is_ok(),
+            ruleset == Ruleset::classic(),
+            "{}",
+            ruleset.name
+        );
+    }
+}
@@ -0,0 +1,93 @@
+extern crate rand;
+extern crate royal_game_of_ur;
+
+use std::path::PathBuf;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use royal_game_of_ur::bot::{self, Bot};
+use royal_game_of_ur::cli::Options;
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::settings::Settings;
+use royal_game_of_ur::Gameboard;
+
+fn parse(args: &[&str]) -> Result<Options, String> {
+    Options::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn options_parse() {
+    assert_eq!(parse(&[]), Ok(Options::default()));
+    let options = parse(&[
+        "--ruleset",
+        "Masters",
+        "--seed",
+        "42",
+        "--p2",
+        "bot:search",
+        "--load",
+        "game.json",
+        "--window",
+        "1280x768",
+        "--headless",
+        "--font",
+        "font.ttf",
+    ])
+    .unwrap();
+    assert_eq!(options.ruleset, Some("Masters".to_string()));
+    assert_eq!(options.seed, Some(42));
+    assert_eq!(options.players, [None, Some("bot:search".to_string())]);
+    assert_eq!(options.load, Some(PathBuf::from("game.json")));
+    assert_eq!(options.window, Some([1280, 768]));
+    assert!(options.headless);
+    assert_eq!(options.font, Some(PathBuf::from("font.ttf")));
+}
+
+#[test]
+fn bad_options_are_rejected() {
+    for args in &[
+        &["--seed"][..],
+        &["--seed", "-1"],
+        &["--window", "1280"],
+        &["--window", "wide"],
+        &["--fullscreen"],
+    ] {
+        assert!(parse(args).is_err(), "{:?}", args);
+    }
+}
+
+#[test]
+fn options_override_settings() {
+    let settings = Settings::default();
+    let options = parse(&["--p1", "bot:greedy", "--ruleset", "Masters"]).unwrap();
+    let applied = options.apply(&settings).unwrap();
+    assert_eq!(applied.players(), [Some(Bot::Greedy), None]);
+    assert_eq!(applied.ruleset, "Masters");
+    assert_eq!(applied.window, settings.window);
+
+    for args in &[
+        &["--p1", "bot:nobody"][..],
+        &["--ruleset", "Chess"],
+        &["--window", "10x10"],
+    ] {
+        assert!(parse(args).unwrap().apply(&settings).is_err(), "{:?}", args);
+    }
+}
+
+#[test]
+fn bots_play_out_a_game() {
+    let gameboard = Gameboard::with_ruleset(Ruleset::classic());
+    let bots = [Bot::Greedy, Bot::Greedy];
+    let record = bot::play_out(gameboard, bots, None, &mut StdRng::seed_from_u64(7), None);
+    let last = record.position(record.turns.len()).unwrap();
+    assert!(last.is_finished());
+    let again = bot::play_out(
+        Gameboard::with_ruleset(Ruleset::classic()),
+        bots,
+        None,
+        &mut StdRng::seed_from_u64(7),
+        None,
+    );
+    assert_eq!(again, record);
+}
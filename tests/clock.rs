@@ -0,0 +1,119 @@
+extern crate rand;
+extern crate royal_game_of_ur;
+
+use std::env;
+use std::io;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use royal_game_of_ur::clock::{self, Clock, TimeControl, Timeout};
+use royal_game_of_ur::save::SaveGame;
+use royal_game_of_ur::{Gameboard, Player};
+
+fn control(game: Option<f64>, increment: f64, per_move: Option<f64>) -> TimeControl {
+    TimeControl {
+        game,
+        increment,
+        per_move,
+        timeout: Timeout::Forfeit,
+    }
+}
+
+#[test]
+fn game_clock_counts_down_with_increments() {
+    let mut clock = Clock::new(control(Some(60.), 2., None));
+    assert!(!clock.tick(Player::First, 10.));
+    assert_eq!(clock.time_left(Player::First), Some(50.));
+    assert_eq!(clock.time_left(Player::Second), Some(60.));
+    clock.end_move(Player::First);
+    assert_eq!(clock.remaining, [52., 60.]);
+
+    assert!(clock.tick(Player::Second, 61.));
+    assert_eq!(clock.time_left(Player::Second), Some(0.));
+    assert_eq!(clock.winner(), None);
+    clock.forfeited = Some(Player::Second);
+    assert_eq!(clock.winner(), Some(Player::First));
+    // A forfeited game's clocks stop.
+    assert!(!clock.tick(Player::First, 100.));
+}
+
+#[test]
+fn move_limit_restarts_each_move() {
+    let mut clock = Clock::new(control(Some(100.), 0., Some(15.)));
+    assert!(!clock.tick(Player::First, 10.));
+    assert_eq!(clock.time_left(Player::First), Some(5.));
+    clock.end_move(Player::First);
+    assert_eq!(clock.time_left(Player::Second), Some(15.));
+    assert!(clock.tick(Player::Second, 15.));
+
+    let mut untimed_game = Clock::new(control(None, 0., Some(15.)));
+    assert!(!untimed_game.tick(Player::First, 14.));
+    assert_eq!(untimed_game.time_left(Player::First), Some(1.));
+}
+
+#[test]
+fn time_controls_describe_themselves() {
+    assert_eq!(control(Some(300.), 3., None).label(), "5 min + 3 s");
+    assert_eq!(control(None, 0., Some(15.)).label(), "15 s per move");
+    assert_eq!(
+        control(Some(180.), 0., Some(20.)).label(),
+        "3 min, 20 s per move"
+    );
+    assert_eq!(clock::format_time(65.2), "1:06");
+    assert_eq!(clock::format_time(4.25), "4.2");
+}
+
+#[test]
+fn timeouts_play_a_legal_move() {
+    let mut g = Gameboard::from_notation("5100001000000000/6000000010000000 1 2").unwrap();
+    let mut rng = StdRng::seed_from_u64(1);
+    for &timeout in &Timeout::ALL {
+        let cell = clock::timeout_move(&g, timeout, None, &mut rng).unwrap();
+        assert!(g.legal_moves().contains(&cell));
+    }
+    g.dice_roll = Some(0);
+    assert_eq!(
+        clock::timeout_move(&g, Timeout::Random, None, &mut rng),
+        None
+    );
+}
+
+#[test]
+fn clocks_are_saved() {
+    let mut clock = Clock::new(control(Some(60.), 1., Some(10.)));
+    clock.tick(Player::First, 3.5);
+    let save = SaveGame {
+        clock: Some(clock),
+        ..SaveGame::new(&Gameboard::new())
+    };
+    let path = env::temp_dir().join("royal_game_of_ur_clock_test.json");
+    save.write(&path).unwrap();
+    assert_eq!(SaveGame::read(&path).unwrap(), save);
+}
+
+#[test]
+fn damaged_time_controls_are_rejected() {
+    for control in TimeControl::presets() {
+        assert_eq!(control.validate(), Ok(()), "{}", control.label());
+    }
+    let damaged = [
+        control(Some(0.), 0., None),
+        control(Some(-60.), 0., None),
+        control(None, 0., Some(-1.)),
+        control(Some(60.), -5., None),
+        control(Some(60.), f64::NAN, None),
+        control(None, 0., Some(f64::INFINITY)),
+    ];
+    for control in &damaged {
+        assert!(control.validate().is_err(), "{:?}", control);
+    }
+    let save = SaveGame {
+        clock: Some(Clock::new(damaged[3])),
+        ..SaveGame::new(&Gameboard::new())
+    };
+    let path = env::temp_dir().join("royal_game_of_ur_damaged_clock_test.json");
+    save.write(&path).unwrap();
+    let e = SaveGame::read(&path).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,110 @@
+extern crate rand;
+extern crate royal_game_of_ur;
+extern crate serde_json;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use royal_game_of_ur::dice::Dice;
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::Gameboard;
+
+/// Rolls `dice` many times and checks each total's frequency against the
+/// exact distribution.
+fn assert_samples_match(dice: &Dice) {
+    let distribution = dice.distribution();
+    let mut counts = vec![0u32; distribution.len()];
+    let mut rng = StdRng::seed_from_u64(7);
+    let rolls = 100_000;
+    for _ in 0..rolls {
+        counts[dice.roll(&mut rng) as usize] += 1;
+    }
+    for (count, p) in counts.into_iter().zip(distribution) {
+        assert!((f64::from(count) / f64::from(rolls) - p).abs() < 0.01);
+    }
+}
+
+#[test]
+fn distributions_are_exact() {
+    let sixteenths = |counts: &[f64]| counts.iter().map(|n| n / 16.0).collect::<Vec<_>>();
+    assert_eq!(
+        Dice::Binary(4).distribution(),
+        sixteenths(&[1., 4., 6., 4., 1.])
+    );
+    assert_eq!(
+        Dice::Uniform(1, 4).distribution(),
+        vec![0., 0.25, 0.25, 0.25, 0.25]
+    );
+    assert_eq!(
+        Dice::Remapped {
+            dice: 4,
+            from: 0,
+            to: 5
+        }
+        .distribution(),
+        sixteenths(&[0., 4., 6., 4., 1., 1.])
+    );
+    assert_eq!(Dice::Uniform(1, 4).outcomes().len(), 4);
+    assert_eq!(Dice::Binary(3).max(), 3);
+}
+
+#[test]
+fn rolls_follow_the_distribution() {
+    assert_samples_match(&Dice::Binary(4));
+    assert_samples_match(&Dice::Uniform(1, 4));
+    assert_samples_match(&Dice::Remapped {
+        dice: 4,
+        from: 2,
+        to: 0,
+    });
+    assert_samples_match(&Dice::table(vec![0.1, 0.0, 0.6, 0.3]).unwrap());
+}
+
+#[test]
+fn tables_must_be_distributions() {
+    assert!(Dice::table(vec![0.5, 0.25]).is_none());
+    assert!(Dice::table(vec![1.5, -0.5]).is_none());
+}
+
+#[test]
+fn invalid_dice_are_rejected() {
+    for json in &[
+        r#"{"Uniform": [4, 1]}"#,
+        r#"{"Uniform": [1, 200]}"#,
+        r#"{"Uniform": [1, 127]}"#,
+        r#"{"Binary": 200}"#,
+        r#"{"Remapped": {"dice": 4, "from": 5, "to": 5}}"#,
+        r#"{"Remapped": {"dice": 4, "from": 0, "to": 200}}"#,
+        r#"{"Table": [0.5, 0.25]}"#,
+        r#"{"Table": [1.5, -0.5]}"#,
+    ] {
+        let dice: Dice = serde_json::from_str(json).unwrap();
+        assert!(dice.validate().is_err(), "{}", json);
+        let ruleset = Ruleset {
+            dice,
+            ..Ruleset::classic()
+        };
+        assert!(ruleset.validate().is_err(), "{}", json);
+    }
+}
+
+#[test]
+fn rulesets_roll_their_dice() {
+    let ruleset = Ruleset {
+        dice: Dice::Uniform(1, 4),
+        ..Ruleset::classic()
+    };
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut g = Gameboard::with_ruleset(ruleset.clone());
+    for _ in 0..100 {
+        assert!((1..5).contains(&g.roll(&mut rng)));
+    }
+    assert!(Gameboard::from_notation_with_ruleset(
+        Ruleset {
+            dice: Dice::Binary(2),
+            ..ruleset
+        },
+        "7000000000000000/7000000000000000 1 3"
+    )
+    .is_none());
+}
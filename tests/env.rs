@@ -0,0 +1,82 @@
+extern crate royal_game_of_ur;
+
+use royal_game_of_ur::dice::Dice;
+use royal_game_of_ur::env::{Environment, ACTIONS, OBSERVATION_SIZE};
+use royal_game_of_ur::rules::Ruleset;
+
+#[test]
+fn every_observation_has_a_legal_action() {
+    let mut env = Environment::new(7);
+    assert_eq!(env.actions(), ACTIONS);
+    assert_eq!(env.observation_size(), OBSERVATION_SIZE);
+    let mut observation = vec![0.0; OBSERVATION_SIZE];
+
+    for game in 0..20 {
+        env.reset(Some(game));
+        let mut done = false;
+        while !done {
+            env.observe(&mut observation);
+            let roll = observation[32..37].iter().position(|&x| x == 1.0).unwrap();
+            assert!(roll > 0);
+
+            let mask = env.legal_action_mask();
+            let action = (0..ACTIONS).rev().find(|&a| mask[a]).unwrap();
+            let step = env.step(action).unwrap();
+            done = step.done;
+            assert_eq!(step.reward, if done { 1.0 } else { 0.0 });
+        }
+        assert!(env.gameboard().winner().is_some());
+    }
+}
+
+#[test]
+fn illegal_actions_are_rejected() {
+    let mut env = Environment::new(0);
+    let mask = env.legal_action_mask();
+    let illegal = (0..ACTIONS).find(|&a| !mask[a]).unwrap();
+    let before = env.gameboard().to_notation();
+
+    assert!(env.step(illegal).is_none());
+    assert!(env.step(ACTIONS).is_none());
+    assert_eq!(env.gameboard().to_notation(), before);
+}
+
+#[test]
+fn same_seed_same_game() {
+    let mut a = Environment::new(42);
+    let mut b = Environment::new(1);
+    b.reset(Some(42));
+    assert_eq!(a.gameboard().to_notation(), b.gameboard().to_notation());
+
+    let action = a.legal_action_mask().iter().position(|&x| x).unwrap();
+    a.step(action).unwrap();
+    b.step(action).unwrap();
+    assert_eq!(a.gameboard().to_notation(), b.gameboard().to_notation());
+}
+
+#[test]
+fn sizes_follow_the_ruleset() {
+    let ruleset = Ruleset {
+        dice: Dice::Uniform(1, 6),
+        ..Ruleset::masters()
+    };
+    let mut env = Environment::with_ruleset(ruleset, 3);
+    assert_eq!(env.actions(), 17);
+    assert_eq!(env.observation_size(), 18 + 18 + 7 + 1);
+
+    let mut observation = vec![0.0; env.observation_size()];
+    for game in 0..5 {
+        env.reset(Some(game));
+        let mut done = false;
+        while !done {
+            env.observe(&mut observation);
+            let roll = observation[36..43].iter().position(|&x| x == 1.0).unwrap();
+            assert!((1..=6).contains(&roll));
+
+            let mask = env.legal_action_mask();
+            assert_eq!(mask.len(), env.actions());
+            let action = mask.iter().position(|&x| x).unwrap();
+            done = env.step(action).unwrap().done;
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[test]
+fn c_smoke_test() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // The cdylib is built next to the test binaries in `target/<profile>/deps`.
+    let lib_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let exe = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ffi_smoke");
+
+    let status = Command::new("cc")
+        .arg(manifest_dir.join("tests/ffi_smoke.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lroyal_game_of_ur")
+        .arg("-o")
+        .arg(&exe)
+        .status()
+        .expect("Could not run the C compiler");
+    assert!(status.success());
+
+    // Cargo's own library path would take precedence over the rpath.
+    let status = Command::new(&exe)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let generated =
+        fs::read_to_string(Path::new(env!("OUT_DIR")).join("royal_game_of_ur.h")).unwrap();
+    let checked_in = fs::read_to_string(manifest_dir.join("include/royal_game_of_ur.h")).unwrap();
+    assert!(
+        generated == checked_in,
+        "include/royal_game_of_ur.h is stale; rebuild with UR_UPDATE_HEADER=1"
+    );
+}
@@ -0,0 +1,46 @@
+/* Smoke test for the C ABI, compiled and run by tests/ffi.rs. */
+
+#include <assert.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "royal_game_of_ur.h"
+
+int main(void) {
+    char notation[64];
+    uint8_t moves[16];
+    uint64_t seed = 1;
+
+    UrGame *game = ur_new();
+    assert(ur_to_notation(game, notation, sizeof notation) == 37);
+    assert(strcmp(notation, "7000000000000000/7000000000000000 1 -") == 0);
+    assert(ur_winner(game) == 0);
+
+    /* Pass until a roll lets the first player enter a piece. */
+    while (ur_roll(game, seed++) == 0) {
+        ur_pass_turn(game);
+    }
+    size_t count = ur_legal_moves(game, moves, sizeof moves);
+    assert(count == 1 && moves[0] == 0);
+    assert(!ur_apply_move(game, 14));
+
+    UrGame *copy = ur_copy(game);
+    assert(ur_apply_move(game, 0));
+    ur_to_notation(game, notation, sizeof notation);
+    assert(notation[0] == '6');
+    ur_to_notation(copy, notation, sizeof notation);
+    assert(notation[0] == '7');
+    ur_free(copy);
+    ur_free(game);
+
+    assert(ur_from_notation("not a position") == NULL);
+    game = ur_from_notation("0000000000000007/7000000000000000 2 -");
+    assert(game != NULL);
+    assert(ur_winner(game) == 1);
+    ur_free(game);
+    ur_free(NULL);
+
+    puts("ffi smoke test passed");
+    return 0;
+}
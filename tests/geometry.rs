@@ -0,0 +1,41 @@
+extern crate royal_game_of_ur;
+
+use royal_game_of_ur::geometry::BoardGeometry;
+use royal_game_of_ur::{Gameboard, Player};
+
+#[test]
+fn standard_tracks_round_trip() {
+    let geometry = BoardGeometry::STANDARD;
+    assert_eq!(geometry.path_len(), 16);
+    for &player in &[Player::First, Player::Second] {
+        for path in 0..geometry.path_len() {
+            let cell = geometry.grid_cell(player, path).unwrap();
+            assert_eq!(geometry.path_cell(player, cell), Some(path));
+        }
+        assert_eq!(geometry.grid_cell(player, 16), None);
+    }
+    assert_eq!(geometry.path_cell(Player::First, (2, 0)), None);
+    assert_eq!(geometry.path_cell(Player::Second, (9, 9)), None);
+}
+
+#[test]
+fn gaps_are_not_squares() {
+    let geometry = BoardGeometry::STANDARD;
+    let gaps: Vec<_> = (0..3)
+        .flat_map(|row| (0..8).map(move |column| (row, column)))
+        .filter(|&cell| !geometry.is_square(cell))
+        .collect();
+    assert_eq!(gaps, vec![(0, 4), (0, 5), (2, 4), (2, 5)]);
+}
+
+#[test]
+fn captures_only_on_the_shared_lane() {
+    // Second player on path cells 4 (private) and 6 (shared).
+    let mut g = Gameboard::from_notation("6001000000000000/5000101000000000 1 3").unwrap();
+    assert!(g._move(3));
+    assert_eq!(g.to_notation(), "6000001000000000/6000100000000000 2 -");
+
+    g = Gameboard::from_notation("6100000000000000/5000101000000000 1 3").unwrap();
+    assert!(g._move(1));
+    assert_eq!(g.player_2[4], 1);
+}
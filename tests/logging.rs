@@ -0,0 +1,82 @@
+extern crate log;
+extern crate rand;
+extern crate royal_game_of_ur;
+
+use std::env;
+use std::fs;
+
+use log::LevelFilter;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use royal_game_of_ur::bot::{self, Bot};
+use royal_game_of_ur::logging::{self, GameEvent, GameLog};
+use royal_game_of_ur::{Gameboard, Player};
+
+#[test]
+fn levels_parse() {
+    assert_eq!(logging::parse_level("debug"), Ok(LevelFilter::Debug));
+    assert_eq!(logging::parse_level("OFF"), Ok(LevelFilter::Off));
+    assert!(logging::parse_level("loud").is_err());
+}
+
+#[test]
+fn moves_describe_captures() {
+    let g = Gameboard::from_notation("5100001000000000/6000000010000000 1 2").unwrap();
+    assert_eq!(
+        GameEvent::play(&g, 6),
+        GameEvent::Move {
+            player: Player::First,
+            from: 6,
+            to: 8,
+            capture: true,
+        }
+    );
+    assert_eq!(
+        GameEvent::play(&g, 1),
+        GameEvent::Move {
+            player: Player::First,
+            from: 1,
+            to: 3,
+            capture: false,
+        }
+    );
+}
+
+#[test]
+fn game_logs_round_trip() {
+    let dir = env::temp_dir().join("royal_game_of_ur_game_log_test");
+    let _ = fs::remove_dir_all(&dir);
+
+    let mut log = GameLog::create(&dir).unwrap();
+    let mut other = GameLog::create(&dir).unwrap();
+    assert_ne!(log.path(), other.path());
+
+    let gameboard = Gameboard::new();
+    let bots = [Bot::Greedy, Bot::Greedy];
+    let mut rng = StdRng::seed_from_u64(3);
+    let record = bot::play_out(gameboard, bots, None, &mut rng, Some(&mut log));
+
+    let entries = GameLog::read(log.path()).unwrap();
+    assert_eq!(entries[0].event, GameEvent::start(&Gameboard::new()));
+    let winner = record
+        .position(record.turns.len())
+        .unwrap()
+        .winner()
+        .unwrap();
+    assert_eq!(entries.last().unwrap().event, GameEvent::End { winner });
+    let plays = entries
+        .iter()
+        .filter(|entry| matches!(entry.event, GameEvent::Move { .. } | GameEvent::Pass { .. }))
+        .count();
+    assert_eq!(plays, record.turns.len());
+    assert!(entries.windows(2).all(|w| w[0].time <= w[1].time));
+
+    other
+        .write(&GameEvent::Pass {
+            player: Player::Second,
+        })
+        .unwrap();
+    let line = fs::read_to_string(other.path()).unwrap();
+    assert!(line.contains("\"event\":\"pass\""), "{}", line);
+}
@@ -0,0 +1,58 @@
+extern crate rand;
+extern crate royal_game_of_ur;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use royal_game_of_ur::luck::Accounting;
+use royal_game_of_ur::record::GameRecord;
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::{Gameboard, Player};
+
+#[test]
+fn rolls_are_luck_and_choices_are_skill() {
+    // A roll of 2 hits the capture; the alternative is a worse move.
+    let mut g = Gameboard::from_notation("5100001000000000/6000000010000000 1 2").unwrap();
+    let mut record = GameRecord::new(Ruleset::classic());
+    assert!(record.play(&mut g, 1));
+    let accounting = Accounting::new(&record, 2);
+    let turn = &accounting.turns[0];
+    assert_eq!(turn.player, Player::First);
+    assert!(turn.luck > 0.0);
+    assert!(turn.skill < 0.0);
+    assert_eq!(accounting.skill(Player::Second), 0.0);
+
+    // Rolling 0 is bad luck, and passing is the only option.
+    let mut g = Gameboard::from_notation("5100001000000000/6000000010000000 1 0").unwrap();
+    let mut record = GameRecord::new(Ruleset::classic());
+    record.pass(&mut g);
+    let accounting = Accounting::new(&record, 2);
+    assert!(accounting.luck(Player::First) < 0.0);
+    assert_eq!(accounting.skill(Player::First), 0.0);
+}
+
+#[test]
+fn cumulative_totals_follow_each_player() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let mut g = Gameboard::new();
+    let mut record = GameRecord::new(g.ruleset.clone());
+    while !g.is_finished() {
+        g.roll(&mut rng);
+        let moves = g.legal_moves();
+        if moves.is_empty() {
+            record.pass(&mut g);
+        } else {
+            let cell = moves[rng.gen_range(0, moves.len())];
+            assert!(record.play(&mut g, cell));
+        }
+    }
+    let accounting = Accounting::new(&record, 1);
+    for &player in &[Player::First, Player::Second] {
+        let cumulative = accounting.cumulative(player);
+        let turns = accounting.turns.iter().filter(|t| t.player == player);
+        assert_eq!(cumulative.len(), turns.clone().count());
+        let luck: f64 = turns.clone().map(|t| t.luck).sum();
+        assert!((accounting.luck(player) - luck).abs() < 1e-9);
+        assert!(cumulative.windows(2).all(|w| w[1].1 <= w[0].1));
+    }
+}
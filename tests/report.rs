@@ -0,0 +1,79 @@
+extern crate rand;
+extern crate royal_game_of_ur;
+extern crate serde_json;
+
+use std::env;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use royal_game_of_ur::analysis::DEFAULT_DEPTH;
+use royal_game_of_ur::bot;
+use royal_game_of_ur::record::GameRecord;
+use royal_game_of_ur::report::Report;
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::{Gameboard, Player};
+
+#[test]
+fn missed_capture_is_a_blunder() {
+    let mut g = Gameboard::from_notation("5100001000000000/6000000010000000 1 2").unwrap();
+    let mut record = GameRecord::new(Ruleset::classic());
+    assert!(record.play(&mut g, 1));
+    assert!(!record.play(&mut g, 1));
+    assert_eq!(record.turns.len(), 1);
+
+    let report = Report::new(&record, DEFAULT_DEPTH);
+    assert_eq!(report.blunders.len(), 1);
+    let blunder = &report.blunders[0];
+    assert_eq!(blunder.player, Player::First);
+    assert_eq!((blunder.played, blunder.best), (Some(1), 6));
+    assert!(report.accuracy(Player::First) < 100.0);
+    assert_eq!(report.accuracy(Player::Second), 100.0);
+
+    assert!(report
+        .to_markdown()
+        .contains("| 1 | First | `5100001000000000/6000000010000000 1 2` | 1 | 6 |"));
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["blunders"][0]["best"], 6);
+}
+
+#[test]
+fn bot_games_produce_reports() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let mut g = Gameboard::new();
+    let mut record = GameRecord::new(g.ruleset.clone());
+    while !g.is_finished() {
+        g.roll(&mut rng);
+        match bot::choose_move(&g) {
+            Some(cell) => assert!(record.play(&mut g, cell)),
+            None => record.pass(&mut g),
+        }
+    }
+    let report = Report::new(&record, 1);
+    for &player in &[Player::First, Player::Second] {
+        let accuracy = report.accuracy(player);
+        assert!(accuracy > 0.0 && accuracy <= 100.0);
+    }
+    assert!(report.blunders.iter().all(|b| b.loss > 5.0));
+}
+
+#[test]
+fn records_replay_to_the_final_position() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut g = Gameboard::new();
+    let mut record = GameRecord::new(g.ruleset.clone());
+    while !g.is_finished() {
+        g.roll(&mut rng);
+        match bot::Bot::Greedy.choose(&g, None) {
+            Some(cell) => assert!(record.play(&mut g, cell)),
+            None => record.pass(&mut g),
+        }
+    }
+    let path = env::temp_dir().join("royal_game_of_ur_record_test.json");
+    record.write(&path).unwrap();
+    let read = GameRecord::read(&path).unwrap();
+    assert_eq!(read, record);
+    let last = read.position(read.turns.len()).unwrap();
+    assert_eq!(last.to_notation(), g.to_notation());
+    assert!(read.position(read.turns.len() + 1).is_none());
+}
@@ -0,0 +1,267 @@
+extern crate royal_game_of_ur;
+
+use std::env;
+use std::io;
+
+use royal_game_of_ur::dice::Dice;
+use royal_game_of_ur::record::GameRecord;
+use royal_game_of_ur::rules::{ExtraTurns, Ruleset, Trigger};
+use royal_game_of_ur::save::SaveGame;
+use royal_game_of_ur::{Gameboard, Player};
+
+fn position(ruleset: Ruleset, notation: &str) -> Gameboard {
+    Gameboard::from_notation_with_ruleset(ruleset, notation).unwrap()
+}
+
+#[test]
+fn classic_is_the_default() {
+    assert_eq!(Gameboard::new().ruleset, Ruleset::classic());
+    // Landing on the central rosette passes the turn and can be captured.
+    let mut g = position(Ruleset::classic(), "6000000100000000/6000100000000000 1 1");
+    assert!(g._move(7));
+    assert_eq!(g.active_player, Player::Second);
+    g.dice_roll = Some(4);
+    assert!(g._move(4));
+    assert_eq!(g.player_1[0], 7);
+}
+
+#[test]
+fn rosettes_grant_another_turn() {
+    let mut g = position(Ruleset::finkel(), "6000000100000000/7000000000000000 1 1");
+    assert!(g._move(7));
+    assert_eq!(g.active_player, Player::First);
+    assert_eq!(g.dice_roll, None);
+
+    // The private rosette on path cell 4.
+    let mut g = position(Ruleset::finkel(), "6100000000000000/7000000000000000 1 3");
+    assert!(g._move(1));
+    assert_eq!(g.active_player, Player::First);
+}
+
+#[test]
+fn safe_squares_block_captures() {
+    let g = position(Ruleset::finkel(), "6000000010000000/6000100000000000 2 4");
+    assert!(!g.legal_moves().contains(&4));
+
+    let g = position(Ruleset::classic(), "6000000010000000/6000100000000000 2 4");
+    assert!(g.legal_moves().contains(&4));
+}
+
+#[test]
+fn custom_piece_and_dice_counts() {
+    let ruleset = Ruleset {
+        name: "Quick".to_string(),
+        pieces: 3,
+        dice: Dice::Binary(2),
+        ..Ruleset::bell()
+    };
+    let g = Gameboard::with_ruleset(ruleset.clone());
+    assert_eq!(g.player_1[0], 3);
+    assert!(!g.is_finished());
+    assert!(Gameboard::from_notation_with_ruleset(
+        ruleset.clone(),
+        "3000000000000000/3000000000000000 1 3"
+    )
+    .is_none());
+
+    let g = position(ruleset, "0000000000000003/2000000000000001 2 -");
+    assert_eq!(g.winner(), Some(Player::First));
+}
+
+#[test]
+fn ten_pieces_use_letters_in_notation() {
+    let ruleset = Ruleset {
+        pieces: 10,
+        ..Ruleset::classic()
+    };
+    let g = Gameboard::with_ruleset(ruleset.clone());
+    assert_eq!(g.to_notation(), "a000000000000000/a000000000000000 1 -");
+
+    let g = position(ruleset, "000000000000000a/9000001000000000 2 -");
+    assert_eq!(g.player_1[15], 10);
+    assert_eq!(g.winner(), Some(Player::First));
+}
+
+#[test]
+fn saves_keep_the_ruleset() {
+    let g = position(Ruleset::bell(), "5100000100000000/7000000000000000 2 -");
+    let path = env::temp_dir().join("royal_game_of_ur_rules_test.json");
+    SaveGame::new(&g).write(&path).unwrap();
+    let save = SaveGame::read(&path).unwrap();
+
+    assert_eq!(save.ruleset, Ruleset::bell());
+    let restored = save.to_gameboard().unwrap();
+    assert_eq!(restored.to_notation(), g.to_notation());
+    assert_eq!(restored.ruleset, g.ruleset);
+}
+
+#[test]
+fn damaged_rulesets_are_rejected() {
+    for ruleset in Ruleset::presets() {
+        assert_eq!(ruleset.validate(), Ok(()), "{}", ruleset.name);
+    }
+    let damaged = vec![
+        Ruleset {
+            pieces: 0,
+            ..Ruleset::classic()
+        },
+        Ruleset {
+            pieces: 40,
+            ..Ruleset::classic()
+        },
+        Ruleset {
+            dice: Dice::Binary(0),
+            ..Ruleset::classic()
+        },
+        Ruleset {
+            dice: Dice::Binary(20),
+            ..Ruleset::classic()
+        },
+        Ruleset {
+            dice: Dice::Table(vec![1.0, 0.0]),
+            ..Ruleset::classic()
+        },
+        Ruleset {
+            rosettes: vec![(1, 4), (3, 0)],
+            ..Ruleset::classic()
+        },
+    ];
+    let path = env::temp_dir().join("royal_game_of_ur_damaged_rules_test.json");
+    for ruleset in damaged {
+        assert!(ruleset.validate().is_err(), "{:?}", ruleset);
+
+        let mut save = SaveGame::new(&Gameboard::new());
+        save.ruleset = ruleset.clone();
+        assert!(save.to_gameboard().is_none());
+
+        GameRecord::new(ruleset).write(&path).unwrap();
+        let e = GameRecord::read(&path).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn impossible_high_rolls_do_not_count() {
+    let mut table = vec![0.0; 20];
+    table[1] = 0.5;
+    table[2] = 0.5;
+    let ruleset = Ruleset {
+        dice: Dice::Table(table),
+        ..Ruleset::classic()
+    };
+    assert_eq!(ruleset.validate(), Ok(()));
+}
+
+#[test]
+fn masters_track_crosses_the_opponents_side() {
+    let geometry = Ruleset::masters().track.geometry();
+    assert_eq!(geometry.path_len(), 18);
+    for &player in &[Player::First, Player::Second] {
+        for path in 0..geometry.path_len() {
+            let cell = geometry.grid_cell(player, path).unwrap();
+            assert_eq!(geometry.path_cell(player, cell), Some(path));
+        }
+    }
+
+    // The first player's path cell 13 is the second player's 15.
+    let mut g = position(
+        Ruleset::masters(),
+        "600000000001000000/600000000000000100 1 2",
+    );
+    assert_eq!(g.to_notation().len(), 41);
+    assert!(g.captures(11));
+    assert!(g._move(11));
+    assert_eq!(g.to_notation(), "600000000000010000/700000000000000000 2 -");
+
+    // Both players' far rosettes are safe.
+    let g = position(
+        Ruleset::masters(),
+        "600000000001000000/600000000000000010 1 1",
+    );
+    assert!(!g.legal_moves().contains(&11));
+    assert!(Gameboard::from_notation_with_ruleset(
+        Ruleset::masters(),
+        "600000000000100000/600000000000000010 1 -"
+    )
+    .is_none());
+}
+
+#[test]
+fn extra_turn_triggers_combine() {
+    let house = Ruleset {
+        extra_turns: ExtraTurns {
+            rosettes: Trigger::Never,
+            captures: Trigger::Always,
+            bearing_off: Trigger::Always,
+        },
+        ..Ruleset::finkel()
+    };
+    // Capturing keeps the turn, landing on a rosette no longer does.
+    let mut g = position(house.clone(), "6000001000000000/6000000100000000 1 1");
+    assert!(g._move(6));
+    assert_eq!(g.active_player, Player::First);
+    assert!(g.extra_turn);
+    g.dice_roll = Some(4);
+    assert!(g._move(0));
+    assert_eq!(g.active_player, Player::Second);
+    assert!(!g.extra_turn);
+
+    let mut g = position(house.clone(), "6000000000000100/7000000000000000 1 2");
+    assert!(g._move(13));
+    assert_eq!(g.active_player, Player::First);
+
+    // Only a roll of four counts for bearing off.
+    let four = Ruleset {
+        extra_turns: ExtraTurns {
+            bearing_off: Trigger::OnRoll(4),
+            ..house.extra_turns
+        },
+        ..house
+    };
+    let mut g = position(four.clone(), "6000000000000100/7000000000000000 1 2");
+    assert!(g._move(13));
+    assert_eq!(g.active_player, Player::Second);
+    let mut g = position(four, "6000000000010000/7000000000000000 1 4");
+    assert!(g._move(11));
+    assert_eq!(g.active_player, Player::First);
+}
+
+#[test]
+fn extra_turn_triggers_have_their_own_rolls() {
+    // Rosettes always grant another turn, captures only on a four.
+    let ruleset = Ruleset {
+        extra_turns: ExtraTurns {
+            captures: Trigger::OnRoll(4),
+            ..ExtraTurns::default()
+        },
+        ..Ruleset::finkel()
+    };
+    assert_eq!(ruleset.extra_turns.label(), "Rosette, capture on 4");
+    assert_eq!(ruleset.validate(), Ok(()));
+
+    let mut g = position(ruleset.clone(), "6100000000000000/7000000000000000 1 3");
+    assert!(g._move(1));
+    assert_eq!(g.active_player, Player::First);
+
+    let mut g = position(ruleset.clone(), "6000001000000000/6000000100000000 1 1");
+    assert!(g._move(6));
+    assert_eq!(g.active_player, Player::Second);
+
+    let mut g = position(ruleset.clone(), "6001000000000000/6000000100000000 1 4");
+    assert!(g._move(3));
+    assert_eq!(g.player_2[0], 7);
+    assert_eq!(g.active_player, Player::First);
+
+    let impossible = Ruleset {
+        extra_turns: ExtraTurns {
+            captures: Trigger::OnRoll(5),
+            ..ruleset.extra_turns
+        },
+        ..ruleset
+    };
+    assert!(impossible.validate().is_err());
+
+    let presets = ExtraTurns::presets();
+    assert_eq!(presets[0], ExtraTurns::default());
+    assert_eq!(presets.last().unwrap().label(), "None");
+}
@@ -0,0 +1,169 @@
+extern crate royal_game_of_ur;
+extern crate serde_json;
+extern crate tungstenite;
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::server::Server;
+
+fn start() -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr();
+    thread::spawn(move || server.run());
+    addr
+}
+
+fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        addr,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn create_and_get_game() {
+    let addr = start();
+    let (status, created) = request(addr, "POST", "/games", "");
+    assert_eq!(status, 201);
+    assert_eq!(created["player_1"][0], 7);
+    assert_eq!(created["active_player"], "First");
+    assert!(created["dice_roll"].is_null());
+
+    let path = format!("/games/{}", created["id"]);
+    let (status, state) = request(addr, "GET", &path, "");
+    assert_eq!(status, 200);
+    assert_eq!(state, created);
+
+    let (status, _) = request(addr, "GET", "/games/999", "");
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn games_take_a_ruleset() {
+    let addr = start();
+    let ruleset = serde_json::to_string(&Ruleset {
+        pieces: 5,
+        ..Ruleset::masters()
+    })
+    .unwrap();
+    let body = format!("{{\"ruleset\": {}}}", ruleset);
+    let (status, created) = request(addr, "POST", "/games", &body);
+    assert_eq!(status, 201);
+    assert_eq!(created["player_1"][0], 5);
+    assert_eq!(created["player_1"].as_array().unwrap().len(), 18);
+
+    // Cells past the track are rejected like any illegal move.
+    let game = format!("/games/{}", created["id"]);
+    request(addr, "POST", &format!("{}/roll", game), "");
+    let (status, _) = request(addr, "POST", &format!("{}/move", game), "{\"cell\": 40}");
+    assert_eq!(status, 409);
+
+    let damaged = ruleset.replace("\"pieces\":5", "\"pieces\":99");
+    let body = format!("{{\"ruleset\": {}}}", damaged);
+    let (status, _) = request(addr, "POST", "/games", &body);
+    assert_eq!(status, 400);
+
+    let body = r#"{"time_control": {"game": -60, "per_move": null}}"#;
+    let (status, _) = request(addr, "POST", "/games", body);
+    assert_eq!(status, 400);
+}
+
+#[test]
+fn roll_move_and_pass() {
+    let addr = start();
+    let (_, created) = request(addr, "POST", "/games", "");
+    let game = format!("/games/{}", created["id"]);
+
+    // Roll until a piece can enter the board.
+    loop {
+        let (status, state) = request(addr, "POST", &format!("{}/roll", game), "");
+        assert_eq!(status, 200);
+        let (status, again) = request(addr, "POST", &format!("{}/roll", game), "");
+        assert_eq!(status, 409, "{}", again);
+
+        let (status, moves) = request(addr, "GET", &format!("{}/moves", game), "");
+        assert_eq!(status, 200);
+        assert_eq!(moves, state["legal_moves"]);
+        if moves.as_array().unwrap().is_empty() {
+            let (status, _) = request(addr, "POST", &format!("{}/pass", game), "");
+            assert_eq!(status, 200);
+            continue;
+        }
+
+        let (status, _) = request(addr, "POST", &format!("{}/move", game), "{\"cell\": 14}");
+        assert_eq!(status, 409);
+        let (status, _) = request(addr, "POST", &format!("{}/move", game), "{}");
+        assert_eq!(status, 400);
+
+        let mover = state["active_player"].clone();
+        let roll = state["dice_roll"].as_u64().unwrap() as usize;
+        let (status, moved) = request(addr, "POST", &format!("{}/move", game), "{\"cell\": 0}");
+        assert_eq!(status, 200);
+        assert_ne!(moved["active_player"], mover);
+        let pieces = if mover == "First" {
+            "player_1"
+        } else {
+            "player_2"
+        };
+        assert_eq!(moved[pieces][0], 6);
+        assert_eq!(moved[pieces][roll], 1);
+        break;
+    }
+}
+
+#[test]
+fn events_are_pushed_over_websocket() {
+    let addr = start();
+    let (_, created) = request(addr, "POST", "/games", "");
+    let url = format!("ws://{}/games/{}/events", addr, created["id"]);
+    let (mut socket, _) = tungstenite::client(&url[..], TcpStream::connect(addr).unwrap()).unwrap();
+
+    let first: Value = serde_json::from_str(socket.read().unwrap().to_text().unwrap()).unwrap();
+    assert_eq!(first["event"], "state");
+    assert_eq!(first["state"], created);
+
+    let (_, passed) = request(addr, "POST", &format!("/games/{}/pass", created["id"]), "");
+    let event: Value = serde_json::from_str(socket.read().unwrap().to_text().unwrap()).unwrap();
+    assert_eq!(event["event"], "pass");
+    assert_eq!(event["state"], passed);
+    assert_eq!(event["state"]["active_player"], "Second");
+}
+
+#[test]
+fn clocks_run_out_between_requests() {
+    let addr = start();
+    let body = r#"{"time_control": {"game": null, "per_move": 0.2, "timeout": "forfeit"}}"#;
+    let (status, created) = request(addr, "POST", "/games", body);
+    assert_eq!(status, 201);
+    assert_eq!(created["clock"]["control"]["per_move"], 0.2);
+    assert!(created["winner"].is_null());
+
+    thread::sleep(Duration::from_millis(300));
+    let (status, state) = request(addr, "GET", &format!("/games/{}", created["id"]), "");
+    assert_eq!(status, 200);
+    assert_eq!(state["clock"]["forfeited"], "First");
+    assert_eq!(state["winner"], "Second");
+    let roll = format!("/games/{}/roll", created["id"]);
+    assert_eq!(request(addr, "POST", &roll, "").0, 409);
+
+    let (status, _) = request(addr, "POST", "/games", "{\"time_control\": 5}");
+    assert_eq!(status, 400);
+}
@@ -0,0 +1,76 @@
+extern crate royal_game_of_ur;
+
+use std::env;
+use std::fs;
+
+use royal_game_of_ur::bot::Bot;
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::settings::{self, Settings};
+
+#[test]
+fn defaults_are_valid() {
+    let settings = Settings::default();
+    assert_eq!(settings.validate(), Ok(()));
+    assert_eq!(settings.players(), [None, None]);
+    assert_eq!(settings.ruleset(), Some(Ruleset::default()));
+}
+
+#[test]
+fn settings_round_trip() {
+    let settings = Settings {
+        window: [1280, 768],
+        theme: "dark".to_string(),
+        bot_delay: 0.2,
+        second_player: "bot:search".to_string(),
+        ruleset: "Masters".to_string(),
+        pieces: 5,
+        ..Settings::default()
+    };
+    let path = env::temp_dir().join("royal_game_of_ur_settings_test.toml");
+    settings.write(&path).unwrap();
+    assert_eq!(Settings::read(&path).unwrap(), settings);
+    assert_eq!(settings.players(), [None, Some(Bot::Search)]);
+    assert_eq!(settings.ruleset().unwrap().pieces, 5);
+
+    // Missing fields take their defaults.
+    fs::write(&path, "theme = \"dark\"\n").unwrap();
+    let partial = Settings::read(&path).unwrap();
+    assert_eq!(partial.theme, "dark");
+    assert_eq!(partial.window, Settings::default().window);
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    let path = env::temp_dir().join("royal_game_of_ur_invalid_settings_test.toml");
+    for toml in &[
+        "window = [",
+        "window = [10, 10]",
+        "bot_delay = -1.0",
+        "first_player = \"bot:nobody\"",
+        "ruleset = \"Chess\"",
+        "pieces = 0",
+    ] {
+        fs::write(&path, toml).unwrap();
+        assert!(Settings::read(&path).is_err(), "{}", toml);
+    }
+}
+
+#[test]
+fn players_parse() {
+    assert_eq!(settings::parse_player("human"), Ok(None));
+    for &bot in &Bot::ALL {
+        let name = settings::player_name(Some(bot));
+        assert_eq!(settings::parse_player(&name), Ok(Some(bot)));
+    }
+    assert!(settings::parse_player("bot:").is_err());
+    assert!(settings::parse_player("greedy").is_err());
+}
+
+#[test]
+fn data_files_live_next_to_the_settings() {
+    let path = settings::data_path("savegame.json");
+    match settings::config_dir() {
+        Some(dir) => assert_eq!(path, dir.join("savegame.json")),
+        None => assert_eq!(path.to_str(), Some("savegame.json")),
+    }
+}
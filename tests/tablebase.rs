@@ -0,0 +1,87 @@
+extern crate flate2;
+extern crate royal_game_of_ur;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Write};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use royal_game_of_ur::analysis;
+use royal_game_of_ur::bitboard::Position;
+use royal_game_of_ur::bot;
+use royal_game_of_ur::rules::Ruleset;
+use royal_game_of_ur::tablebase::{positions, Tablebase};
+use royal_game_of_ur::Gameboard;
+
+#[test]
+fn one_piece_endgames() {
+    let mut sweeps = 0;
+    let table = Tablebase::generate(7, 1, |sweep, _| sweeps = sweep);
+    assert!(sweeps > 1);
+    assert_eq!(table.len(), positions(7, 1).len());
+    assert!(table.verify() < 1e-9);
+
+    // One step from home, needing exactly a 1, against a piece in reserve.
+    let near = Gameboard::from_notation("0000000000000016/1000000000000006 1 -").unwrap();
+    let far = Gameboard::from_notation("0000000000000016/1000000000000006 2 -").unwrap();
+    let mirrored = Gameboard::from_notation("1000000000000006/0000000000000016 2 -").unwrap();
+    let near_value = table.probe_gameboard(&near).unwrap();
+    assert!(near_value > 0.5 && near_value < 1.0);
+    assert!(table.probe_gameboard(&far).unwrap() < 0.5);
+    assert!((table.probe_gameboard(&mirrored).unwrap() - near_value).abs() < 1e-9);
+    assert_eq!(table.probe(&Position::new()), None);
+    assert_eq!(
+        table.probe_gameboard(
+            &Gameboard::from_notation_with_ruleset(
+                Ruleset::finkel(),
+                "0000000000000016/1000000000000006 1 -"
+            )
+            .unwrap()
+        ),
+        None
+    );
+
+    // The search returns table values for covered positions.
+    let analysis = analysis::analyse_with(&near, 2, Some(&table));
+    assert_eq!(analysis.first_player, near_value);
+}
+
+#[test]
+fn files_round_trip() {
+    let table = Tablebase::generate(3, 2, |_, _| {});
+    let path = env::temp_dir().join("royal_game_of_ur_tablebase_test.urtb");
+    table.write(&path).unwrap();
+    let read = Tablebase::read(&path).unwrap();
+    assert_eq!((read.pieces(), read.max_remaining()), (3, 2));
+    assert_eq!(read.len(), table.len());
+    assert!(read.verify() < 1e-4);
+    for position in positions(3, 2) {
+        let error = read.probe(&position).unwrap() - table.probe(&position).unwrap();
+        assert!(error.abs() < 1e-4);
+    }
+}
+
+#[test]
+fn impossible_headers_are_rejected() {
+    let path = env::temp_dir().join("royal_game_of_ur_bad_tablebase_test.urtb");
+    for &(pieces, max_remaining) in &[(7, 0), (2, 3), (16, 16), (255, 1)] {
+        let mut out = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        out.write_all(b"URTB").unwrap();
+        out.write_all(&[1, pieces, max_remaining]).unwrap();
+        out.finish().unwrap();
+        match Tablebase::read(&path) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            Ok(_) => panic!("read {} pieces, {} remaining", pieces, max_remaining),
+        }
+    }
+}
+
+#[test]
+fn bot_prefers_the_table_move() {
+    let table = Tablebase::generate(7, 2, |_, _| {});
+    // Hitting the opponent's last piece beats running home with a roll of 2.
+    let g = Gameboard::from_notation("0100001000000005/0000000010000006 1 2").unwrap();
+    assert_eq!(bot::choose_move_with(&g, &table), Some(6));
+}
@@ -0,0 +1,93 @@
+extern crate royal_game_of_ur;
+
+use royal_game_of_ur::theme::{self, PieceShape, Theme};
+
+#[test]
+fn presets_are_distinct() {
+    let presets = Theme::presets();
+    let names: Vec<&str> = presets.iter().map(|t| &t.name[..]).collect();
+    assert_eq!(names, ["classic", "high contrast", "deuteranopia", "dark"]);
+    assert_eq!(Theme::default(), presets[0]);
+    // The players never share a colour, and high contrast also differs in shape.
+    for theme in &presets {
+        assert_ne!(theme.player_1_color, theme.player_2_color);
+    }
+    assert_ne!(presets[1].player_1_shape, presets[1].player_2_shape);
+}
+
+#[test]
+fn user_themes_replace_presets_by_name() {
+    let classic = Theme {
+        player_2_shape: PieceShape::Ring,
+        ..Theme::default()
+    };
+    let renamed = Theme {
+        name: "mine".to_string(),
+        ..classic.clone()
+    };
+    let toml = format!(
+        "{}\n{}",
+        toml_theme(&classic, "ring"),
+        toml_theme(&renamed, "ring")
+    );
+    let extra = Theme::parse(&toml).unwrap();
+    assert_eq!(extra, vec![classic.clone(), renamed]);
+
+    let mut themes = Theme::presets();
+    theme::merge(&mut themes, extra);
+    assert_eq!(themes.len(), 5);
+    assert_eq!(themes[0], classic);
+    assert_eq!(themes[4].name, "mine");
+}
+
+#[test]
+fn invalid_themes_are_rejected() {
+    let theme = Theme::default();
+    // Missing colours.
+    assert!(Theme::parse("[[theme]]\nname = \"bare\"\n").is_err());
+    // Unknown shape.
+    assert!(Theme::parse(&toml_theme(&theme, "hexagon")).is_err());
+    // Colour out of range.
+    let bright = toml_theme(&theme, "dot").replace("focus_color = [0.1,", "focus_color = [1.5,");
+    assert!(bright.contains("1.5"));
+    assert!(Theme::parse(&bright).is_err());
+}
+
+/// `theme` as a `[[theme]]` table, with `shape` for the second player.
+fn toml_theme(theme: &Theme, shape: &str) -> String {
+    let color = |c: [f32; 4]| format!("[{}, {}, {}, {}]", c[0], c[1], c[2], c[3]);
+    format!(
+        "[[theme]]\nname = \"{}\"\nwindow_color = {}\nbackground_color = {}\n\
+         border_color = {}\nboard_edge_color = {}\nsection_edge_color = {}\n\
+         cell_edge_color = {}\nplayer_1_color = {}\nplayer_2_color = {}\n\
+         player_1_board_color = {}\nplayer_2_board_color = {}\nrosette_color = {}\n\
+         focus_color = {}\nplayer_2_shape = \"{}\"\n",
+        theme.name,
+        color(theme.window_color),
+        color(theme.background_color),
+        color(theme.border_color),
+        color(theme.board_edge_color),
+        color(theme.section_edge_color),
+        color(theme.cell_edge_color),
+        color(theme.player_1_color),
+        color(theme.player_2_color),
+        color(theme.player_1_board_color),
+        color(theme.player_2_board_color),
+        color(theme.rosette_color),
+        color(theme.focus_color),
+        shape
+    )
+}
+
+#[test]
+fn loading_keeps_the_presets() {
+    // Tests run from the repository root, whose assets hold the presets.
+    let themes = Theme::load();
+    for preset in Theme::presets() {
+        assert!(
+            themes.iter().any(|t| t.name == preset.name),
+            "{}",
+            preset.name
+        );
+    }
+}